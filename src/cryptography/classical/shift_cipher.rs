@@ -1,4 +1,5 @@
 //! Shift Cipher Implementation
+use alloc::{string::String, vec::Vec};
 
 /// Implements a modernized shift cipher or Caesar cipher for String
 ///
@@ -1,7 +1,37 @@
 //! Errors enum
-use std::string::FromUtf8Error;
+//!
+//! With the std feature the errors implement std::error::Error through thiserror. Without alloc there
+//! is nowhere to format a message into, so the number theory errors carry the unformatted message
+//! instead, such as "No modular multiplicative inverse exists {} mod {}".
+#[cfg(feature = "alloc")]
+use alloc::string::{FromUtf8Error, String};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "std")]
 use thiserror::Error;
 
+/// Error message, formatted with alloc and a static string without it
+#[cfg(feature = "alloc")]
+pub type Message = String;
+/// Error message, formatted with alloc and a static string without it
+#[cfg(not(feature = "alloc"))]
+pub type Message = &'static str;
+
+/// Formats an error Message, or keeps the format string as is without alloc
+macro_rules! message {
+    ($format: literal $(, $argument: expr)* $(,)?) => {{
+        #[cfg(feature = "alloc")]
+        let message = alloc::format!($format $(, $argument)*);
+        #[cfg(not(feature = "alloc"))]
+        let message = {
+            $(let _ = &$argument;)*
+            $format
+        };
+        message
+    }};
+}
+pub(crate) use message;
+
 //TODO: Update with cryptography errors
 
 /// Library errors enumeration
@@ -9,6 +39,7 @@ use thiserror::Error;
 /// Lists all possible errors that can occur in the Library
 #[allow(dead_code)]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum MathLibErrors {
     NumberTheoryErr,
 }
@@ -17,19 +48,27 @@ pub enum MathLibErrors {
 ///
 /// Errors within the number theory module
 #[allow(dead_code)]
-#[derive(Debug, Error)]
+#[derive(Debug)]
+#[cfg_attr(feature = "std", derive(Error))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum NumberTheoryErr {
-    #[error("Operation overflow error:\n{0}\n")]
-    Overflow(String),
-    #[error("Inverse does not exist:\n{0}\n")]
-    InverseDNE(String),
+    #[cfg_attr(feature = "std", error("Operation overflow error:\n{0}\n"))]
+    Overflow(Message),
+    #[cfg_attr(feature = "std", error("Inverse does not exist:\n{0}\n"))]
+    InverseDNE(Message),
+    #[cfg_attr(feature = "std", error("Input is outside of the domain:\n{0}\n"))]
+    Domain(Message),
+    #[cfg_attr(feature = "std", error("No solution exists:\n{0}\n"))]
+    NoSolution(Message),
 }
 
 /// 2D Geometry errors
 ///
 /// Errors within 2D geometry
 #[allow(dead_code)]
-#[derive(Debug, Error)]
+#[derive(Debug)]
+#[cfg_attr(feature = "std", derive(Error))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Geometry2DErr {
     // TODO: Fill out as needed
 }
@@ -38,7 +77,9 @@ pub enum Geometry2DErr {
 ///
 /// Errors within 2D geometry
 #[allow(dead_code)]
-#[derive(Debug, Error)]
+#[derive(Debug)]
+#[cfg_attr(feature = "std", derive(Error))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Geometry3DErr {
     // TODO: Fill out as needed
 }
@@ -47,10 +88,93 @@ pub enum Geometry3DErr {
 ///
 /// Errors within the classical cryptography module
 #[allow(dead_code)]
-#[derive(Debug, Error)]
+#[cfg(feature = "alloc")]
+#[derive(Debug)]
+#[cfg_attr(feature = "std", derive(Error))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum ClassicalCryptographyErr {
-    #[error("Error creating string from Utf8 bytes : \n{0}\n")]
-    FromUtf8Err(#[from] FromUtf8Error),
-    #[error("Invalid affine cipher key. The gcd of the alpha, {0} and the max value of u8, {1} is not 1")]
+    #[cfg_attr(
+        feature = "std",
+        error("Error creating string from Utf8 bytes : \n{0}\n")
+    )]
+    FromUtf8Err(
+        #[cfg_attr(feature = "std", from)]
+        #[cfg_attr(feature = "serde", serde(with = "from_utf8_error"))]
+        FromUtf8Error,
+    ),
+    #[cfg_attr(
+        feature = "std",
+        error("Invalid affine cipher key. The gcd of the alpha, {0} and the max value of u8, {1} is not 1")
+    )]
     AffineKeyError(u8, u8),
 }
+
+/// FromUtf8Error is stored as the bytes that failed to convert, which fail the same way when read back
+#[cfg(feature = "serde")]
+mod from_utf8_error {
+    use alloc::{
+        string::{FromUtf8Error, String},
+        vec::Vec,
+    };
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        error: &FromUtf8Error,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(error.as_bytes())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<FromUtf8Error, D::Error> {
+        let bytes = Vec::<u8>::deserialize(deserializer)?;
+        String::from_utf8(bytes)
+            .err()
+            .ok_or_else(|| D::Error::custom("the bytes of a FromUtf8Error are valid UTF-8"))
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn number_theory_err_serde_test() {
+        let error =
+            NumberTheoryErr::InverseDNE("No modular multiplicative inverse exists 2 mod 4".into());
+        let json = serde_json::to_string(&error).unwrap();
+        assert_eq!(
+            r#"{"InverseDNE":"No modular multiplicative inverse exists 2 mod 4"}"#,
+            json
+        );
+        let read: NumberTheoryErr = serde_json::from_str(&json).unwrap();
+        assert!(
+            matches!(read, NumberTheoryErr::InverseDNE(message) if message == "No modular multiplicative inverse exists 2 mod 4")
+        );
+    }
+
+    #[test]
+    fn classical_cryptography_err_serde_test() {
+        let error: ClassicalCryptographyErr = String::from_utf8(vec![b'a', 0xff, b'b'])
+            .unwrap_err()
+            .into();
+        let json = serde_json::to_string(&error).unwrap();
+        assert_eq!(r#"{"FromUtf8Err":[97,255,98]}"#, json);
+        let read: ClassicalCryptographyErr = serde_json::from_str(&json).unwrap();
+        assert!(
+            matches!(read, ClassicalCryptographyErr::FromUtf8Err(e) if e.as_bytes() == b"a\xffb")
+        );
+        assert!(
+            serde_json::from_str::<ClassicalCryptographyErr>(r#"{"FromUtf8Err":[97]}"#).is_err()
+        );
+
+        let error = ClassicalCryptographyErr::AffineKeyError(4, 255);
+        let json = serde_json::to_string(&error).unwrap();
+        let read: ClassicalCryptographyErr = serde_json::from_str(&json).unwrap();
+        assert!(matches!(
+            read,
+            ClassicalCryptographyErr::AffineKeyError(4, 255)
+        ));
+    }
+}
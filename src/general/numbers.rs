@@ -1,7 +1,9 @@
 //! Numerical Traits - For more convenient trait bounding
-use num::{Bounded, CheckedMul, FromPrimitive, Num, NumCast, ToPrimitive};
+use core::ops::{AddAssign, DivAssign, MulAssign, RemAssign, SubAssign};
+use num::{
+    Bounded, CheckedAdd, CheckedMul, CheckedSub, FromPrimitive, Num, NumCast, PrimInt, ToPrimitive,
+};
 use rand::distributions::uniform::SampleUniform;
-use std::ops::{AddAssign, DivAssign, MulAssign, RemAssign, SubAssign};
 
 pub trait Number:
     Num
@@ -37,10 +39,25 @@ impl<T> Number for T where
 {
 }
 
-pub trait Integer: Number + Bounded + Ord + CheckedMul {}
+pub trait Integer: Number + Bounded + Ord + CheckedAdd + CheckedSub + CheckedMul + PrimInt {}
 
-impl<T> Integer for T where T: Number + Bounded + Ord + CheckedMul {}
+impl<T> Integer for T where
+    T: Number + Bounded + Ord + CheckedAdd + CheckedSub + CheckedMul + PrimInt
+{
+}
 
 pub trait Float: Number + PartialOrd {}
 
 impl<T> Float for T where T: Number + PartialOrd {}
+
+/// Absolute value
+///
+/// Absolute value for any Integer, signed or unsigned. The absolute value of T::min_value() is not
+/// representable for signed types and will overflow.
+pub fn abs<T: Integer>(n: T) -> T {
+    if n < T::zero() {
+        T::zero() - n
+    } else {
+        n
+    }
+}
@@ -1,3 +1,10 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "alloc")]
+pub mod cryptography;
 pub mod general;
 pub mod number_theory;
 pub mod prelude;
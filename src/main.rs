@@ -1,7 +1,3 @@
-mod cryptography;
-mod general;
-mod number_theory;
-
 use git_version::git_version;
 
 const GIT_VERSION: &str = git_version!();
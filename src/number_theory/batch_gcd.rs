@@ -0,0 +1,214 @@
+//! Batch GCD: Bernstein's product and remainder trees
+//!
+//! Finds the gcd of each input with the product of all the other inputs in quasi-linear time, rather than
+//! computing the gcd of every pair. This is the standard way to audit a set of RSA moduli for shared primes.
+//! See: https://facthacks.cr.yp.to/batchgcd.html
+use alloc::{vec, vec::Vec};
+use num::{BigUint, Integer, One, Zero};
+
+/// Product Tree
+///
+/// Levels of pairwise products, starting from the values themselves and ending with a root level holding
+/// the product of every value
+pub fn product_tree(values: &[BigUint]) -> Vec<Vec<BigUint>> {
+    let mut tree = vec![values.to_vec()];
+    while tree.last().unwrap().len() > 1 {
+        let level = tree
+            .last()
+            .unwrap()
+            .chunks(2)
+            .map(|pair| pair.iter().product())
+            .collect();
+        tree.push(level);
+    }
+    tree
+}
+
+/// Remainder Tree
+///
+/// Reduce a value modulo every leaf of a product tree by reducing it down the tree one level at a time,
+/// so each reduction is by a modulus only as large as the subtree below it
+pub fn remainder_tree(value: &BigUint, tree: &[Vec<BigUint>]) -> Vec<BigUint> {
+    let mut remainders = vec![value.clone()];
+    for level in tree.iter().rev() {
+        remainders = level
+            .iter()
+            .enumerate()
+            .map(|(i, node)| &remainders[i / 2] % node)
+            .collect();
+    }
+    remainders
+}
+
+/// Batch GCD
+///
+/// For each value n_i, return gcd(n_i, product of every other value). The product P is reduced modulo
+/// n_i^2 through a remainder tree, after which gcd(n_i, (P mod n_i^2) / n_i) is the desired gcd.
+///
+/// Every value must be non-zero.
+pub fn batch_gcd(values: &[BigUint]) -> Vec<BigUint> {
+    assert!(
+        values.iter().all(|n| !n.is_zero()),
+        "Batch GCD inputs must be non-zero"
+    );
+    if values.is_empty() {
+        return vec![];
+    }
+
+    let tree = product_tree(values);
+    let squares: Vec<Vec<BigUint>> = tree
+        .iter()
+        .map(|level| level.iter().map(|node| node * node).collect())
+        .collect();
+    let product = &tree.last().unwrap()[0];
+    remainder_tree(product, &squares)
+        .into_iter()
+        .zip(values)
+        .map(|(remainder, n)| (remainder / n).gcd(n))
+        .collect()
+}
+
+/// Shared Factors
+///
+/// Recover factors from a batch GCD. For each value, return a non-trivial factorization (d, n / d) with
+/// d <= n / d when it shares a factor with another value, or None when it shares nothing.
+///
+/// When every prime of a value is shared, the batch gcd is the value itself. Those values fall back to
+/// pairwise gcds with the others, and stay None if no pair splits them (e.g. exact duplicates).
+pub fn shared_factors(values: &[BigUint]) -> Vec<Option<(BigUint, BigUint)>> {
+    batch_gcd(values)
+        .into_iter()
+        .zip(values)
+        .enumerate()
+        .map(|(i, (g, n))| {
+            if g.is_one() {
+                None
+            } else if g != *n {
+                Some((n / &g, g))
+            } else {
+                values
+                    .iter()
+                    .enumerate()
+                    .filter(|(j, _)| *j != i)
+                    .map(|(_, other)| n.gcd(other))
+                    .find(|d| !d.is_one() && d != n)
+                    .map(|d| (n / &d, d))
+            }
+            .map(|(a, b)| if a <= b { (a, b) } else { (b, a) })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn big(values: &[u64]) -> Vec<BigUint> {
+        values.iter().map(|v| BigUint::from(*v)).collect()
+    }
+
+    #[test]
+    fn product_tree_test() {
+        let tree = product_tree(&big(&[2, 3, 5, 7, 11]));
+        assert_eq!(
+            vec![
+                big(&[2, 3, 5, 7, 11]),
+                big(&[6, 35, 11]),
+                big(&[210, 11]),
+                big(&[2310])
+            ],
+            tree
+        );
+    }
+
+    #[test]
+    fn remainder_tree_test() {
+        let values = big(&[7, 11, 13, 17, 19]);
+        let tree = product_tree(&values);
+        let value = BigUint::from(123456789u64);
+        let expected: Vec<BigUint> = values.iter().map(|n| &value % n).collect();
+        assert_eq!(expected, remainder_tree(&value, &tree));
+    }
+
+    #[rstest]
+    #[case(vec![], vec![])]
+    #[case(vec![15], vec![1])]
+    #[case(vec![15, 21], vec![3, 3])]
+    #[case(vec![15, 21, 35], vec![15, 21, 35])]
+    #[case(vec![15, 22, 49], vec![1, 1, 1])]
+    #[case(vec![6, 6], vec![6, 6])]
+    fn batch_gcd_test(#[case] values: Vec<u64>, #[case] expected: Vec<u64>) {
+        assert_eq!(big(&expected), batch_gcd(&big(&values)))
+    }
+
+    #[test]
+    fn batch_gcd_matches_pairwise_test() {
+        // Toy RSA moduli built from a small pool of primes, some of them reused
+        let primes = [
+            1000003u64, 1000033, 1000037, 1000039, 1000081, 1000099, 1000117, 1000121,
+        ];
+        let moduli = big(&[
+            primes[0] * primes[1],
+            primes[2] * primes[3],
+            primes[4] * primes[0],
+            primes[5] * primes[6],
+            primes[7] * primes[3],
+            primes[2] * primes[4],
+        ]);
+        let expected: Vec<BigUint> = moduli
+            .iter()
+            .enumerate()
+            .map(|(i, n)| {
+                let others: BigUint = moduli
+                    .iter()
+                    .enumerate()
+                    .filter(|(j, _)| *j != i)
+                    .map(|(_, m)| m)
+                    .product();
+                n.gcd(&others)
+            })
+            .collect();
+        assert_eq!(expected, batch_gcd(&moduli));
+
+        let factors = shared_factors(&moduli);
+        assert_eq!(
+            Some((BigUint::from(primes[0]), BigUint::from(primes[1]))),
+            factors[0]
+        );
+        assert_eq!(None, factors[3]);
+        for (n, factor) in moduli.iter().zip(&factors) {
+            if let Some((a, b)) = factor {
+                assert_eq!(*n, a * b);
+            }
+        }
+    }
+
+    #[test]
+    fn shared_factors_fallback_test() {
+        // 35 shares 5 with 15 and 7 with 77, so its batch gcd is 35 itself
+        let factors = shared_factors(&big(&[15, 35, 77, 143, 221]));
+        assert_eq!(
+            vec![
+                Some((3, 5)),
+                Some((5, 7)),
+                Some((7, 11)),
+                Some((11, 13)),
+                Some((13, 17)),
+            ]
+            .into_iter()
+            .map(|f| f.map(|(a, b)| (BigUint::from(a as u64), BigUint::from(b as u64))))
+            .collect::<Vec<_>>(),
+            factors
+        );
+
+        // Exact duplicates cannot be split
+        assert_eq!(vec![None, None], shared_factors(&big(&[221, 221])));
+    }
+
+    #[test]
+    #[should_panic]
+    fn batch_gcd_zero_test() {
+        batch_gcd(&big(&[15, 0]));
+    }
+}
@@ -0,0 +1,628 @@
+//! Primality Certificates: Pratt and Pocklington/BLS
+//!
+//! A certificate is a proof of primality that is much cheaper to check than to find. Both kinds are
+//! trees: proving p prime needs the factorization of p - 1, and each prime in that factorization gets
+//! a certificate of its own.
+//!
+//! - Pratt: a witness a of order exactly p - 1, so (Z/pZ)* is cyclic of order p - 1 and p is prime.
+//!   Every prime of p - 1 is certified, down to 2.
+//! - Pocklington: only a factored part F of p - 1 is needed, with F > √p. Brillhart, Lehmer and
+//!   Selfridge lowered this to F >= ∛p plus a perfect square test. Primes below 2^64 are leaves, checked
+//!   by Miller-Rabin to the first 12 prime bases, which is deterministic there.
+//!
+//! The verifiers are deliberately independent of the rest of the crate. They only use modular
+//! exponentiation, gcd and plain arithmetic, never a primality test or a factorization.
+//!
+//! Certificates serialize to a bracketed text format with Display and parse back with FromStr:
+//!
+//! - Pratt: `{p, a, [q1 certificate, q2 certificate, ...]}`
+//! - Pocklington leaf: `{p}`
+//! - Pocklington: `{p, [(q1, e1, a1, q1 certificate), ...]}`
+use crate::{
+    general::errors::{message, NumberTheoryErr},
+    number_theory::{const_fns::MILLER_RABIN_BASES_U64, factorization::BigPrimality},
+};
+use num::{BigUint, Integer, One, Zero};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::{fmt, iter::Peekable, str::Chars, str::FromStr};
+
+/// Pocklington leaves are primes below this bound
+const LEAF_BITS: u64 = 64;
+
+/// Pratt Certificate
+///
+/// witness has order prime - 1 modulo prime, and factors certifies each distinct prime of prime - 1
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PrattCertificate {
+    pub prime: BigUint,
+    pub witness: BigUint,
+    pub factors: Vec<PrattCertificate>,
+}
+
+/// Pocklington/BLS Certificate
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum PocklingtonCertificate {
+    /// A prime below 2^64
+    Leaf(BigUint),
+    /// A prime with its factored part of prime - 1
+    Node {
+        prime: BigUint,
+        factors: Vec<PocklingtonFactor>,
+    },
+}
+
+/// One prime power q^e dividing n - 1, with witness^(n - 1) = 1 and gcd(witness^((n - 1) / q) - 1, n) = 1
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PocklingtonFactor {
+    pub exponent: u32,
+    pub witness: BigUint,
+    pub certificate: PocklingtonCertificate,
+}
+
+/// Primality Certificates Trait
+///
+/// Generates certificates for primes, None for anything else. Generation factors p - 1 with
+/// prime_factorization, which dominates the running time.
+pub trait PrimalityCertificates {
+    fn pratt_certificate(&self) -> Option<PrattCertificate>;
+    fn pocklington_certificate(&self) -> Option<PocklingtonCertificate>;
+}
+
+impl PrimalityCertificates for BigUint {
+    /// Pratt Certificate
+    ///
+    /// Searches for the smallest primitive root. The search is bounded in case the probable prime test
+    /// was wrong, since a composite has no element of order n - 1.
+    fn pratt_certificate(&self) -> Option<PrattCertificate> {
+        let p = self;
+        if !p.is_probable_prime() {
+            return None;
+        }
+        let p_minus_one = p - 1u32;
+        let mut primes = p_minus_one.prime_factorization();
+        primes.dedup();
+
+        let mut witness = BigUint::one();
+        while &witness < p {
+            let is_generator = witness.modpow(&p_minus_one, p).is_one()
+                && primes
+                    .iter()
+                    .all(|q| !witness.modpow(&(&p_minus_one / q), p).is_one());
+            if is_generator {
+                let factors = primes
+                    .iter()
+                    .map(|q| q.pratt_certificate())
+                    .collect::<Option<Vec<_>>>()?;
+                return Some(PrattCertificate {
+                    prime: p.clone(),
+                    witness,
+                    factors,
+                });
+            }
+            witness += 1u32;
+        }
+        None
+    }
+
+    /// Pocklington/BLS Certificate
+    ///
+    /// Takes the prime powers of n - 1 from the smallest until the factored part F satisfies the
+    /// Brillhart-Lehmer-Selfridge condition or F^2 > n, then finds a witness for each of them
+    fn pocklington_certificate(&self) -> Option<PocklingtonCertificate> {
+        let n = self;
+        if !n.is_probable_prime() {
+            return None;
+        } else if n.bits() <= LEAF_BITS {
+            return Some(PocklingtonCertificate::Leaf(n.clone()));
+        }
+        let n_minus_one = n - 1u32;
+        let factorization = n_minus_one.prime_factorization();
+
+        // Group into prime powers and take them until the factored part is large enough
+        let mut powers: Vec<(BigUint, u32)> = vec![];
+        for q in factorization {
+            match powers.last_mut() {
+                Some((last, exponent)) if *last == q => *exponent += 1,
+                _ => powers.push((q, 1)),
+            }
+        }
+        let mut f = BigUint::one();
+        let mut used = 0;
+        while !factored_part_suffices(n, &f) {
+            let (q, exponent) = &powers[used];
+            f *= num::pow(q.clone(), *exponent as usize);
+            used += 1;
+        }
+
+        let mut factors = vec![];
+        for (q, exponent) in powers.into_iter().take(used) {
+            let cofactor = &n_minus_one / &q;
+            let mut witness = BigUint::from(2u32);
+            while !is_pocklington_witness(&witness, n, &cofactor) {
+                witness += 1u32;
+                if &witness >= n {
+                    return None;
+                }
+            }
+            factors.push(PocklingtonFactor {
+                exponent,
+                witness,
+                certificate: q.pocklington_certificate()?,
+            });
+        }
+        Some(PocklingtonCertificate::Node {
+            prime: n.clone(),
+            factors,
+        })
+    }
+}
+
+/// witness^(n - 1) = 1 (mod n) and gcd(witness^((n - 1) / q) - 1, n) = 1, given cofactor = (n - 1) / q
+fn is_pocklington_witness(witness: &BigUint, n: &BigUint, cofactor: &BigUint) -> bool {
+    let partial = witness.modpow(cofactor, n);
+    // partial^q = witness^(n - 1), with q = (n - 1) / cofactor
+    let q = (n - 1u32) / cofactor;
+    partial.modpow(&q, n).is_one() && ((partial + n - 1u32) % n).gcd(n).is_one()
+}
+
+/// Whether a fully certified factored part f of n - 1 proves n prime: Pocklington when f^2 > n, and
+/// Brillhart-Lehmer-Selfridge when f^3 >= n, writing n = c2 f^2 + c1 f + 1 with 0 <= c1 < f and
+/// requiring that c1^2 - 4 c2 is not a perfect square
+fn factored_part_suffices(n: &BigUint, f: &BigUint) -> bool {
+    if f * f > *n {
+        return true;
+    } else if f * f * f < *n {
+        return false;
+    }
+    let (c2, c1) = ((n - 1u32) / f).div_rem(f);
+    let square = &c1 * &c1;
+    let four_c2 = c2 * 4u32;
+    if square < four_c2 {
+        return true;
+    }
+    let d = square - four_c2;
+    let root = d.sqrt();
+    &root * &root != d
+}
+
+impl PrattCertificate {
+    /// Verify
+    ///
+    /// Checks that witness^(p - 1) = 1 and witness^((p - 1) / q) != 1 for every listed q, that the
+    /// listed primes account for all of p - 1, and that each of them verifies recursively
+    pub fn verify(&self) -> bool {
+        let p = &self.prime;
+        if *p < BigUint::from(2u32) {
+            return false;
+        }
+        let p_minus_one = p - 1u32;
+        if !self.witness.modpow(&p_minus_one, p).is_one() {
+            return false;
+        }
+        let mut remaining = p_minus_one.clone();
+        for factor in &self.factors {
+            let q = &factor.prime;
+            if *q < BigUint::from(2u32) || !(&remaining % q).is_zero() {
+                return false;
+            }
+            while (&remaining % q).is_zero() {
+                remaining /= q;
+            }
+            if self.witness.modpow(&(&p_minus_one / q), p).is_one() || !factor.verify() {
+                return false;
+            }
+        }
+        remaining.is_one()
+    }
+}
+
+impl PocklingtonCertificate {
+    /// The prime this certificate is for
+    pub fn prime(&self) -> &BigUint {
+        match self {
+            PocklingtonCertificate::Leaf(prime) => prime,
+            PocklingtonCertificate::Node { prime, .. } => prime,
+        }
+    }
+
+    /// Verify
+    ///
+    /// Leaves are checked with Miller-Rabin to the first 12 prime bases. Nodes need every factor to
+    /// divide n - 1, have a valid witness and verify recursively, with the product F of the factors
+    /// large enough for Pocklington's theorem or the BLS refinement.
+    pub fn verify(&self) -> bool {
+        match self {
+            PocklingtonCertificate::Leaf(p) => p.bits() <= LEAF_BITS && miller_rabin(p),
+            PocklingtonCertificate::Node { prime: n, factors } => {
+                if *n < BigUint::from(3u32) || n.is_even() {
+                    return false;
+                }
+                let n_minus_one = n - 1u32;
+                let mut f = BigUint::one();
+                for factor in factors {
+                    let q = factor.certificate.prime();
+                    if factor.exponent == 0 || !factor.certificate.verify() {
+                        return false;
+                    }
+                    f *= num::pow(q.clone(), factor.exponent as usize);
+                    if !(&n_minus_one % &f).is_zero()
+                        || !is_pocklington_witness(&factor.witness, n, &(&n_minus_one / q))
+                    {
+                        return false;
+                    }
+                }
+                factored_part_suffices(n, &f)
+            }
+        }
+    }
+}
+
+/// Strong probable prime test to the u64 bases, deterministic for every leaf
+fn miller_rabin(n: &BigUint) -> bool {
+    if *n < BigUint::from(2u32) {
+        return false;
+    }
+    let n_minus_one = n - 1u32;
+    let s = n_minus_one.trailing_zeros().unwrap_or(0);
+    let d = &n_minus_one >> s;
+    'witness: for a in MILLER_RABIN_BASES_U64 {
+        let a = BigUint::from(a);
+        if (&a % n).is_zero() {
+            // n is one of the bases
+            return &a == n;
+        }
+        let mut x = a.modpow(&d, n);
+        if x.is_one() || x == n_minus_one {
+            continue;
+        }
+        for _ in 1..s {
+            x = x.modpow(&BigUint::from(2u32), n);
+            if x == n_minus_one {
+                continue 'witness;
+            }
+        }
+        return false;
+    }
+    true
+}
+
+impl fmt::Display for PrattCertificate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{{{}, {}, [", self.prime, self.witness)?;
+        for (i, factor) in self.factors.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", factor)?;
+        }
+        write!(f, "]}}")
+    }
+}
+
+impl fmt::Display for PocklingtonCertificate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PocklingtonCertificate::Leaf(prime) => write!(f, "{{{}}}", prime),
+            PocklingtonCertificate::Node { prime, factors } => {
+                write!(f, "{{{}, [", prime)?;
+                for (i, factor) in factors.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(
+                        f,
+                        "({}, {}, {}, {})",
+                        factor.certificate.prime(),
+                        factor.exponent,
+                        factor.witness,
+                        factor.certificate
+                    )?;
+                }
+                write!(f, "]}}")
+            }
+        }
+    }
+}
+
+/// Recursive descent parser for the certificate text format, skipping whitespace between tokens
+struct Parser<'a> {
+    chars: Peekable<Chars<'a>>,
+}
+
+impl<'a> Parser<'a> {
+    fn new(s: &'a str) -> Self {
+        Parser {
+            chars: s.chars().peekable(),
+        }
+    }
+
+    fn error(message: &str) -> NumberTheoryErr {
+        NumberTheoryErr::Domain(message!("Malformed primality certificate: {}", message))
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.chars.peek().is_some_and(|c| c.is_whitespace()) {
+            self.chars.next();
+        }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.skip_whitespace();
+        self.chars.peek().copied()
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), NumberTheoryErr> {
+        match self.peek() {
+            Some(c) if c == expected => {
+                self.chars.next();
+                Ok(())
+            }
+            _ => Err(Self::error(&format!("expected '{}'", expected))),
+        }
+    }
+
+    fn number(&mut self) -> Result<BigUint, NumberTheoryErr> {
+        self.skip_whitespace();
+        let mut digits = String::new();
+        while let Some(c) = self.chars.peek().filter(|c| c.is_ascii_digit()) {
+            digits.push(*c);
+            self.chars.next();
+        }
+        digits
+            .parse()
+            .map_err(|_| Self::error("expected a non-negative integer"))
+    }
+
+    /// Comma separated items between open and close, each parsed by item
+    fn list<T>(
+        &mut self,
+        open: char,
+        close: char,
+        mut item: impl FnMut(&mut Self) -> Result<T, NumberTheoryErr>,
+    ) -> Result<Vec<T>, NumberTheoryErr> {
+        self.expect(open)?;
+        let mut items = vec![];
+        if self.peek() == Some(close) {
+            self.chars.next();
+            return Ok(items);
+        }
+        loop {
+            items.push(item(self)?);
+            match self.peek() {
+                Some(',') => self.chars.next(),
+                Some(c) if c == close => {
+                    self.chars.next();
+                    return Ok(items);
+                }
+                _ => return Err(Self::error(&format!("expected ',' or '{}'", close))),
+            };
+        }
+    }
+
+    fn pratt(&mut self) -> Result<PrattCertificate, NumberTheoryErr> {
+        self.expect('{')?;
+        let prime = self.number()?;
+        self.expect(',')?;
+        let witness = self.number()?;
+        self.expect(',')?;
+        let factors = self.list('[', ']', Self::pratt)?;
+        self.expect('}')?;
+        Ok(PrattCertificate {
+            prime,
+            witness,
+            factors,
+        })
+    }
+
+    fn pocklington(&mut self) -> Result<PocklingtonCertificate, NumberTheoryErr> {
+        self.expect('{')?;
+        let prime = self.number()?;
+        if self.peek() == Some('}') {
+            self.chars.next();
+            return Ok(PocklingtonCertificate::Leaf(prime));
+        }
+        self.expect(',')?;
+        let factors = self.list('[', ']', |parser| {
+            parser.expect('(')?;
+            let q = parser.number()?;
+            parser.expect(',')?;
+            let exponent = parser
+                .number()?
+                .try_into()
+                .map_err(|_| Self::error("exponent does not fit in u32"))?;
+            parser.expect(',')?;
+            let witness = parser.number()?;
+            parser.expect(',')?;
+            let certificate = parser.pocklington()?;
+            parser.expect(')')?;
+            if *certificate.prime() != q {
+                return Err(Self::error("factor does not match its certificate"));
+            }
+            Ok(PocklingtonFactor {
+                exponent,
+                witness,
+                certificate,
+            })
+        })?;
+        self.expect('}')?;
+        Ok(PocklingtonCertificate::Node { prime, factors })
+    }
+
+    fn finish<T>(&mut self, value: T) -> Result<T, NumberTheoryErr> {
+        match self.peek() {
+            None => Ok(value),
+            Some(_) => Err(Self::error("unexpected trailing input")),
+        }
+    }
+}
+
+impl FromStr for PrattCertificate {
+    type Err = NumberTheoryErr;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser::new(s);
+        let certificate = parser.pratt()?;
+        parser.finish(certificate)
+    }
+}
+
+impl FromStr for PocklingtonCertificate {
+    type Err = NumberTheoryErr;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser::new(s);
+        let certificate = parser.pocklington()?;
+        parser.finish(certificate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn mersenne(exponent: u32) -> BigUint {
+        (BigUint::one() << exponent) - 1u32
+    }
+
+    /// Smooth part of n - 1 is about n^0.42, between the BLS and Pocklington bounds
+    const BLS_PRIME: &str = "205609143554252989923670515379577308603711";
+
+    #[rstest]
+    #[case(2)]
+    #[case(3)]
+    #[case(7)]
+    #[case(97)]
+    #[case(65537)]
+    #[case(1000000007)]
+    #[case(18446744073709551557)]
+    fn u64_pratt_test(#[case] p: u64) {
+        let certificate = BigUint::from(p).pratt_certificate().unwrap();
+        assert_eq!(BigUint::from(p), certificate.prime);
+        assert!(certificate.verify());
+    }
+
+    #[test]
+    fn pratt_text_test() {
+        // 7 - 1 = 2 * 3 and 3 is a primitive root
+        let certificate = BigUint::from(7u32).pratt_certificate().unwrap();
+        let text = "{7, 3, [{2, 1, []}, {3, 2, [{2, 1, []}]}]}";
+        assert_eq!(text, certificate.to_string());
+        assert_eq!(certificate, text.parse().unwrap());
+    }
+
+    #[test]
+    fn pratt_mersenne_test() {
+        let certificate = mersenne(127).pratt_certificate().unwrap();
+        assert!(certificate.verify());
+        let parsed: PrattCertificate = certificate.to_string().parse().unwrap();
+        assert_eq!(certificate, parsed);
+    }
+
+    #[rstest]
+    #[case(0)]
+    #[case(1)]
+    #[case(561)]
+    #[case(3215031751)]
+    fn u64_not_prime_test(#[case] n: u64) {
+        assert_eq!(None, BigUint::from(n).pratt_certificate());
+        assert_eq!(None, BigUint::from(n).pocklington_certificate());
+    }
+
+    #[test]
+    fn pratt_forgery_test() {
+        // 561 = 3 * 11 * 17 is a Carmichael number, so a^560 = 1 for every unit but no unit has order 560
+        let factors: Vec<PrattCertificate> = [2u32, 5, 7]
+            .iter()
+            .map(|q| BigUint::from(*q).pratt_certificate().unwrap())
+            .collect();
+        let forged = PrattCertificate {
+            prime: BigUint::from(561u32),
+            witness: BigUint::from(2u32),
+            factors,
+        };
+        assert!(!forged.verify());
+
+        // A wrong witness, and a missing factor of p - 1
+        let mut certificate = BigUint::from(97u32).pratt_certificate().unwrap();
+        certificate.witness = BigUint::from(2u32);
+        assert!(!certificate.verify());
+        certificate.witness = BigUint::from(5u32);
+        assert!(certificate.verify());
+        certificate.factors.pop();
+        assert!(!certificate.verify());
+    }
+
+    #[rstest]
+    #[case(mersenne(89))]
+    #[case(mersenne(127))]
+    #[case(BLS_PRIME.parse().unwrap())]
+    fn pocklington_test(#[case] p: BigUint) {
+        let certificate = p.pocklington_certificate().unwrap();
+        assert_eq!(&p, certificate.prime());
+        assert!(certificate.verify());
+        let parsed: PocklingtonCertificate = certificate.to_string().parse().unwrap();
+        assert_eq!(certificate, parsed);
+    }
+
+    #[test]
+    fn pocklington_bls_test() {
+        // The factored part is below √n, so the certificate relies on the BLS square test
+        let n: BigUint = BLS_PRIME.parse().unwrap();
+        let certificate = n.pocklington_certificate().unwrap();
+        let PocklingtonCertificate::Node { factors, .. } = &certificate else {
+            panic!("expected a node");
+        };
+        let f: BigUint = factors
+            .iter()
+            .map(|factor| num::pow(factor.certificate.prime().clone(), factor.exponent as usize))
+            .product();
+        assert!(&f * &f < n && &f * &f * &f >= n);
+        assert!(certificate.verify());
+    }
+
+    #[test]
+    fn pocklington_forgery_test() {
+        // 3215031751 is a strong pseudoprime to the bases 2, 3, 5 and 7, but not to 11
+        assert!(!PocklingtonCertificate::Leaf(BigUint::from(3215031751u64)).verify());
+        assert!(!PocklingtonCertificate::Leaf(mersenne(89)).verify());
+
+        let mut certificate = mersenne(127).pocklington_certificate().unwrap();
+        if let PocklingtonCertificate::Node { factors, .. } = &mut certificate {
+            factors.pop();
+        }
+        assert!(!certificate.verify());
+    }
+
+    #[rstest]
+    #[case("")]
+    #[case("{7, 3, [{2, 1, []}")]
+    #[case("{7, 3, [{2, 1, []}]} trailing")]
+    #[case("{7, x, []}")]
+    #[case("{101, [(5, 2, 2, {3})]}")]
+    fn parse_error_test(#[case] text: &str) {
+        assert!(text.parse::<PrattCertificate>().is_err());
+        assert!(text.parse::<PocklingtonCertificate>().is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn certificate_serde_test() {
+        let n = mersenne(127);
+        let pratt = n.pratt_certificate().unwrap();
+        let json = serde_json::to_string(&pratt).unwrap();
+        let read: PrattCertificate = serde_json::from_str(&json).unwrap();
+        assert_eq!(pratt, read);
+        assert!(read.verify());
+
+        let pocklington = n.pocklington_certificate().unwrap();
+        let json = serde_json::to_string(&pocklington).unwrap();
+        let read: PocklingtonCertificate = serde_json::from_str(&json).unwrap();
+        assert_eq!(pocklington, read);
+        assert!(read.verify());
+    }
+}
@@ -0,0 +1,399 @@
+//! Const Functions: number theory at compile time
+//!
+//! The traits above are generic over Integer, and trait methods cannot be const, so tables of small
+//! primes, inverses or totients built with them have to wait for runtime. These are const fn versions
+//! for u32 and u64 (with i32 and i64 for the signed Bezout coefficients), usable in const items such
+//! as const PRIMES: [u64; 8] = first_primes(), and cross-tested against the traits.
+//!
+//! Narrower unsigned types widen to u32 losslessly with as. Intermediate products are taken in the
+//! double width type, so nothing overflows below the type's maximum.
+
+/// Miller-Rabin bases that are deterministic for every u32, see Jaeschke (1993)
+const MILLER_RABIN_BASES_U32: [u32; 3] = [2, 7, 61];
+
+/// Miller-Rabin bases that are deterministic for every u64: the first 12 primes (up to 37) suffice
+/// below 3.18 * 10^23, see Sorenson and Webster (2015)
+pub(crate) const MILLER_RABIN_BASES_U64: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+macro_rules! const_number_theory {
+    (
+        $t: ty, $signed: ty, $wide: ty, $signed_wide: ty, $bases: expr;
+        $gcd: ident, $egcd: ident, $mod_mul: ident, $mod_pow: ident, $mod_inverse: ident,
+        $euler_totient: ident, $is_prime_trial: ident, $is_prime: ident
+    ) => {
+        /// Greatest common divisor by the Euclidean algorithm, gcd(0, 0) = 0
+        pub const fn $gcd(mut a: $t, mut b: $t) -> $t {
+            while b != 0 {
+                (a, b) = (b, a % b);
+            }
+            a
+        }
+
+        /// Extended Euclidean algorithm
+        ///
+        /// (g, x, y) with ax + by = g = gcd(a, b) >= 0, the same coefficients as ExtendedEuclidean::egcd
+        pub const fn $egcd(a: $signed, b: $signed) -> ($signed, $signed, $signed) {
+            let (mut x, mut y, mut prev_x, mut prev_y, mut rem, mut prev_rem) = (0, 1, 1, 0, b, a);
+            while rem != 0 {
+                let q = prev_rem / rem;
+                (prev_rem, rem) = (rem, prev_rem - q * rem);
+                (prev_x, x) = (x, prev_x - q * x);
+                (prev_y, y) = (y, prev_y - q * y);
+            }
+            if prev_rem < 0 {
+                (-prev_rem, -prev_x, -prev_y)
+            } else {
+                (prev_rem, prev_x, prev_y)
+            }
+        }
+
+        /// a * b (mod m), panics if m is zero
+        pub const fn $mod_mul(a: $t, b: $t, m: $t) -> $t {
+            ((a as $wide * b as $wide) % m as $wide) as $t
+        }
+
+        /// base^exponent (mod m) by square-and-multiply, panics if m is zero
+        pub const fn $mod_pow(base: $t, mut exponent: $t, m: $t) -> $t {
+            let mut result = 1 % m;
+            let mut base = base % m;
+            while exponent > 0 {
+                if exponent & 1 == 1 {
+                    result = $mod_mul(result, base, m);
+                }
+                base = $mod_mul(base, base, m);
+                exponent >>= 1;
+            }
+            result
+        }
+
+        /// The inverse of a modulo m in [0, m), or None when gcd(a, m) != 1 or m is zero
+        pub const fn $mod_inverse(a: $t, m: $t) -> Option<$t> {
+            if m == 0 {
+                return None;
+            }
+            let (mut x, mut prev_x, mut rem, mut prev_rem) = (
+                0 as $signed_wide,
+                1 as $signed_wide,
+                m as $signed_wide,
+                (a % m) as $signed_wide,
+            );
+            while rem != 0 {
+                let q = prev_rem / rem;
+                (prev_rem, rem) = (rem, prev_rem - q * rem);
+                (prev_x, x) = (x, prev_x - q * x);
+            }
+            if prev_rem != 1 {
+                return None;
+            }
+            Some(prev_x.rem_euclid(m as $signed_wide) as $t)
+        }
+
+        /// Euler totient φ(n) by trial division, φ(0) = 0
+        pub const fn $euler_totient(n: $t) -> $t {
+            let mut m = n;
+            let mut totient = n;
+            let mut p = 2;
+            while p <= m / p {
+                if m % p == 0 {
+                    while m % p == 0 {
+                        m /= p;
+                    }
+                    totient -= totient / p;
+                }
+                p += 1;
+            }
+            if m > 1 {
+                totient -= totient / m;
+            }
+            totient
+        }
+
+        /// Primality by trial division with 6k ± 1, the same as Primality::primality
+        pub const fn $is_prime_trial(n: $t) -> bool {
+            if n < 4 {
+                return n > 1;
+            } else if n % 2 == 0 || n % 3 == 0 {
+                return false;
+            }
+            let mut b = 5;
+            while b <= n / b {
+                if n % b == 0 || n % (b + 2) == 0 {
+                    return false;
+                }
+                b += 6;
+            }
+            true
+        }
+
+        /// Deterministic Miller-Rabin
+        ///
+        /// n - 1 = d * 2^s with d odd, n is prime when every base has base^d = 1 or
+        /// base^(d * 2^r) = -1 (mod n) for some r < s. The bases are enough for every value of the type.
+        pub const fn $is_prime(n: $t) -> bool {
+            if n < 2 {
+                return false;
+            }
+            let bases = $bases;
+            let mut i = 0;
+            while i < bases.len() {
+                if n == bases[i] {
+                    return true;
+                } else if n % bases[i] == 0 {
+                    return false;
+                }
+                i += 1;
+            }
+
+            let mut d = n - 1;
+            let mut s = 0;
+            while d % 2 == 0 {
+                d /= 2;
+                s += 1;
+            }
+            let mut i = 0;
+            'bases: while i < bases.len() {
+                let mut x = $mod_pow(bases[i], d, n);
+                i += 1;
+                if x == 1 || x == n - 1 {
+                    continue;
+                }
+                let mut r = 1;
+                while r < s {
+                    x = $mod_mul(x, x, n);
+                    if x == n - 1 {
+                        continue 'bases;
+                    }
+                    r += 1;
+                }
+                return false;
+            }
+            true
+        }
+    };
+}
+
+const_number_theory!(
+    u32, i32, u64, i64, MILLER_RABIN_BASES_U32;
+    gcd_u32, egcd_i32, mod_mul_u32, mod_pow_u32, mod_inverse_u32,
+    euler_totient_u32, is_prime_trial_u32, is_prime_u32
+);
+const_number_theory!(
+    u64, i64, u128, i128, MILLER_RABIN_BASES_U64;
+    gcd_u64, egcd_i64, mod_mul_u64, mod_pow_u64, mod_inverse_u64,
+    euler_totient_u64, is_prime_trial_u64, is_prime_u64
+);
+
+/// Sieve of Eratosthenes, whether each of 0, 1, ..., N - 1 is prime
+pub const fn prime_sieve<const N: usize>() -> [bool; N] {
+    let mut sieve = [true; N];
+    let mut i = 0;
+    while i < N && i < 2 {
+        sieve[i] = false;
+        i += 1;
+    }
+    let mut p = 2;
+    while p * p < N {
+        if sieve[p] {
+            let mut m = p * p;
+            while m < N {
+                sieve[m] = false;
+                m += p;
+            }
+        }
+        p += 1;
+    }
+    sieve
+}
+
+/// The first N primes, 2, 3, 5, ...
+pub const fn first_primes<const N: usize>() -> [u64; N] {
+    let mut primes = [0; N];
+    let mut count = 0;
+    let mut candidate = 2;
+    while count < N {
+        if is_prime_u64(candidate) {
+            primes[count] = candidate;
+            count += 1;
+        }
+        candidate += 1;
+    }
+    primes
+}
+
+/// Euler totients φ(0), φ(1), ..., φ(N - 1) by sieving each prime out of the multiples it divides
+pub const fn totient_table<const N: usize>() -> [u64; N] {
+    let mut totients = [0; N];
+    let mut i = 0;
+    while i < N {
+        totients[i] = i as u64;
+        i += 1;
+    }
+    let mut p = 2;
+    while p < N {
+        // Still untouched, so p is prime
+        if totients[p] == p as u64 {
+            let mut m = p;
+            while m < N {
+                totients[m] -= totients[m] / p as u64;
+                m += p;
+            }
+        }
+        p += 1;
+    }
+    totients
+}
+
+/// Inverses of 0, 1, ..., N - 1 modulo m, with 0 for the residues that have no inverse
+pub const fn inverse_table<const N: usize>(m: u64) -> [u64; N] {
+    let mut inverses = [0; N];
+    let mut i = 0;
+    while i < N {
+        inverses[i] = match mod_inverse_u64(i as u64, m) {
+            Some(inverse) => inverse,
+            None => 0,
+        };
+        i += 1;
+    }
+    inverses
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::number_theory::{
+        euclidean_algs::ExtendedEuclidean, euler_totient::EulerTotient, gcd::Gcd,
+        modular_exponentiation::ModularExponentiation,
+        multiplicative_inverse::MultiplicativeInverse, primality::Primality,
+    };
+    use rstest::rstest;
+
+    const SMALL_PRIMES: [u64; 10] = first_primes();
+    const SIEVE: [bool; 1000] = prime_sieve();
+    const TOTIENTS: [u64; 1000] = totient_table();
+    const INVERSES_MOD_13: [u64; 13] = inverse_table(13);
+    const _: () = assert!(is_prime_u64(18446744073709551557) && !is_prime_u64(u64::MAX));
+
+    #[test]
+    fn compile_time_tables_test() {
+        assert_eq!([2, 3, 5, 7, 11, 13, 17, 19, 23, 29], SMALL_PRIMES);
+        assert_eq!([0, 1, 7, 9, 10, 8, 11, 2, 5, 3, 4, 6, 12], INVERSES_MOD_13);
+        for n in 0..1000u64 {
+            assert_eq!(n.primality(), SIEVE[n as usize]);
+            assert_eq!(n.euler_totient(), TOTIENTS[n as usize]);
+        }
+        assert_eq!([false; 0], prime_sieve::<0>());
+        assert_eq!([false, false, true], prime_sieve::<3>());
+    }
+
+    #[rstest]
+    #[case(0, 0)]
+    #[case(0, 12)]
+    #[case(12, 18)]
+    #[case(17, 5)]
+    #[case(u64::MAX, u64::MAX - 1)]
+    #[case(1 << 40, 6 << 20)]
+    fn u64_gcd_test(#[case] a: u64, #[case] b: u64) {
+        assert_eq!(a.gcd(b), gcd_u64(a, b));
+        if let (Ok(a), Ok(b)) = (u32::try_from(a), u32::try_from(b)) {
+            assert_eq!(a.gcd(b), gcd_u32(a, b));
+        }
+    }
+
+    #[test]
+    fn egcd_test() {
+        for a in -60i64..60 {
+            for b in -60i64..60 {
+                assert_eq!(a.egcd(b), egcd_i64(a, b));
+                let (a, b) = (a as i32, b as i32);
+                assert_eq!(a.egcd(b), egcd_i32(a, b));
+            }
+        }
+        assert_eq!(
+            (i64::MAX - 1).egcd(i64::MAX),
+            egcd_i64(i64::MAX - 1, i64::MAX)
+        );
+    }
+
+    #[rstest]
+    #[case(2, 10, 1000)]
+    #[case(3, 0, 7)]
+    #[case(5, 3, 1)]
+    #[case(u64::MAX - 1, u64::MAX - 2, u64::MAX)]
+    #[case(123456789, 987654321, 1000000007)]
+    #[case(1 << 63, 1 << 62, (1 << 63) + 1)]
+    fn u64_mod_pow_test(#[case] base: u64, #[case] exponent: u64, #[case] m: u64) {
+        assert_eq!(
+            base.mod_pow(exponent, m).unwrap(),
+            mod_pow_u64(base, exponent, m)
+        );
+        assert_eq!(base.mod_mul(exponent, m), mod_mul_u64(base, exponent, m));
+        let (base, exponent, m) = (base as u32, exponent as u32, (m as u32).max(1));
+        assert_eq!(
+            base.mod_pow(exponent, m).unwrap(),
+            mod_pow_u32(base, exponent, m)
+        );
+    }
+
+    #[test]
+    fn mod_inverse_test() {
+        // The Bezout coefficients of the trait version are signed
+        for m in 1i64..60 {
+            for a in 0..120 {
+                let expected = a.multiplicative_inverse(m).ok();
+                assert_eq!(
+                    expected,
+                    mod_inverse_u64(a as u64, m as u64).map(|i| i as i64)
+                );
+                assert_eq!(
+                    expected,
+                    mod_inverse_u32(a as u32, m as u32).map(|i| i as i64)
+                );
+            }
+        }
+        assert_eq!(None, mod_inverse_u64(3, 0));
+        let m = u64::MAX;
+        let inverse = mod_inverse_u64(2, m).unwrap();
+        assert_eq!(1, mod_mul_u64(2, inverse, m));
+    }
+
+    #[test]
+    fn euler_totient_test() {
+        for n in 0u64..2000 {
+            assert_eq!(n.euler_totient(), euler_totient_u64(n));
+            assert_eq!((n as u32).euler_totient(), euler_totient_u32(n as u32));
+        }
+        // u32::MAX = 3 * 5 * 17 * 257 * 65537
+        assert_eq!(1 << 31, euler_totient_u32(u32::MAX));
+    }
+
+    #[test]
+    fn primality_test() {
+        for n in 0u64..20000 {
+            let expected = n.primality();
+            assert_eq!(expected, is_prime_trial_u64(n));
+            assert_eq!(expected, is_prime_u64(n));
+            assert_eq!(expected, is_prime_trial_u32(n as u32));
+            assert_eq!(expected, is_prime_u32(n as u32));
+        }
+    }
+
+    #[rstest]
+    #[case(4294967291, true)]
+    #[case(4294967295, false)]
+    #[case(3215031751, false)]
+    #[case(2152302898747, false)]
+    #[case(3825123056546413051, false)]
+    #[case(1000000000000000003, true)]
+    #[case(999999999999999989, true)]
+    fn large_primality_test(#[case] n: u64, #[case] expected: bool) {
+        assert_eq!(expected, is_prime_u64(n));
+        // Trial division up to √n is only quick enough below about 2^42
+        if n < 1 << 42 {
+            assert_eq!(expected, n.primality());
+            assert_eq!(expected, is_prime_trial_u64(n));
+        }
+        if let Ok(n) = u32::try_from(n) {
+            assert_eq!(expected, is_prime_u32(n));
+        }
+    }
+}
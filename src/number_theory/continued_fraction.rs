@@ -1,10 +1,14 @@
 use crate::general::numbers::Integer;
+use alloc::{vec, vec::Vec};
 
 /// Generalized Continued Fraction
 ///
 /// Returns the set of integer parts of the continued fraction
 /// https://en.wikipedia.org/wiki/Continued_fraction
 /// https://mathworld.wolfram.com/ContinuedFraction.html
+///
+/// The integer parts are found with floor division, so negative fractions produce the canonical expansion
+/// where every term after the first is positive, e.g. -7/3 = [-3; 1, 2]
 pub trait ContinuedFraction<T> {
     fn continued_frac(self, other: T) -> Vec<T>;
 }
@@ -21,8 +25,11 @@ macro_rules! continued_fraction {
                 let mut a = self;
                 let mut b = other;
                 loop {
-                    // Find the integer portion of the fraction
+                    // Find the integer portion of the fraction, rounding towards negative infinity
                     i = a / b;
+                    if a % b != T::zero() && ((a < T::zero()) != (b < T::zero())) {
+                        i -= T::one();
+                    }
                     fraction.push(i);
 
                     // Subtract the integer portion of the fraction
@@ -71,6 +78,11 @@ mod tests {
     #[case(19, 43, vec![0, 2, 3, 1, 4])]
     #[case(7, 3, vec![2, 3])]
     #[case(3, 7, vec![0, 2, 3])]
+    #[case(-7, 3, vec![-3, 1, 2])]
+    #[case(7, -3, vec![-3, 1, 2])]
+    #[case(-7, -3, vec![2, 3])]
+    #[case(-3, 7, vec![-1, 1, 1, 3])]
+    #[case(-649, 200, vec![-4, 1, 3, 12, 4])]
     fn isize_continued_fraction_test(
         #[case] a: isize,
         #[case] b: isize,
@@ -78,4 +90,20 @@ mod tests {
     ) {
         assert_eq!(expected, a.continued_frac(b))
     }
+
+    #[test]
+    fn isize_continued_fraction_negative_range_test() {
+        // Evaluate the continued fraction back into a fraction p/q and compare cross products
+        for a in -60isize..=60 {
+            for b in (-15isize..=15).filter(|b| *b != 0) {
+                let fraction = a.continued_frac(b);
+                assert!(fraction.iter().skip(1).all(|i| *i > 0));
+                let (p, q) = fraction
+                    .iter()
+                    .rev()
+                    .fold((1, 0), |(p, q), i| (i * p + q, p));
+                assert_eq!(a * q, b * p);
+            }
+        }
+    }
 }
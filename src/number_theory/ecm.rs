@@ -0,0 +1,323 @@
+//! Lenstra Elliptic Curve Method (ECM)
+//!
+//! Finds a prime factor p of n by computing a large multiple of a point on a random elliptic curve
+//! modulo n. The point vanishes modulo p when the order of the curve modulo p is smooth, which shows up
+//! as a non-trivial gcd with n. Unlike Pollard's rho, the running time depends on the size of p rather
+//! than n, and every new curve is a fresh chance with a different group order.
+//!
+//! Curves are in Montgomery form By^2 = x^3 + Ax^2 + x with Suyama's parametrization, and points are
+//! kept as projective (X : Z) pairs so no inversions are needed. Stage 1 multiplies by every prime power
+//! up to B1, stage 2 catches a single remaining prime in (B1, B2] with a baby-step giant-step walk.
+//! See: https://members.loria.fr/PZimmermann/papers/ecm-submitted.pdf
+use crate::number_theory::prime_counting::segmented_sieve;
+use num::{BigInt, BigUint, Integer, One};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// Giant step size for stage 2, 2 * 3 * 5 * 7 * 11 keeps the number of baby steps small
+const STAGE_TWO_STEP: u64 = 2310;
+
+/// ECM Configuration
+///
+/// The stage 1 bound B1, stage 2 bound B2, the number of curves to try and the seed for choosing them.
+/// The defaults suit factors of around 20 digits, use roughly B1 = 2000 for 15 digits and B1 = 50000
+/// for 25 digits, with B2 = 100 * B1.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EcmConfig {
+    pub b1: u64,
+    pub b2: u64,
+    pub curves: usize,
+    pub seed: u64,
+}
+
+impl Default for EcmConfig {
+    fn default() -> Self {
+        EcmConfig {
+            b1: 11000,
+            b2: 1100000,
+            curves: 100,
+            seed: 0,
+        }
+    }
+}
+
+/// A point on a Montgomery curve in projective (X : Z) coordinates
+#[derive(Clone, Debug)]
+struct Point {
+    x: BigUint,
+    z: BigUint,
+}
+
+/// A Montgomery curve modulo n, given by (A + 2) / 4 which is all the x-only formulas need
+struct Curve<'a> {
+    n: &'a BigUint,
+    a24: BigUint,
+}
+
+/// The curve and starting point for Suyama's parameter σ, or a factor of n found while inverting
+enum CurveSetup<'a> {
+    Curve(Curve<'a>, Point),
+    Factor(BigUint),
+    Failed,
+}
+
+/// Inverse of a modulo n, or the gcd of a and n when it does not exist
+fn inverse(a: &BigUint, n: &BigUint) -> Result<BigUint, BigUint> {
+    let (a, n) = (BigInt::from(a.clone()), BigInt::from(n.clone()));
+    let egcd = a.extended_gcd(&n);
+    if egcd.gcd.is_one() {
+        Ok(egcd.x.mod_floor(&n).to_biguint().unwrap())
+    } else {
+        Err(egcd.gcd.to_biguint().unwrap())
+    }
+}
+
+impl<'a> Curve<'a> {
+    /// Suyama's parametrization, whose group orders are divisible by 12
+    ///
+    /// With u = σ^2 - 5 and v = 4σ the starting point is (u^3 : v^3) and
+    /// (A + 2) / 4 = (v - u)^3 (3u + v) / (16 u^3 v)
+    fn suyama(n: &'a BigUint, sigma: u64) -> CurveSetup<'a> {
+        let sigma = BigUint::from(sigma) % n;
+        let u = (&sigma * &sigma + n - BigUint::from(5u32) % n) % n;
+        let v = (&sigma * 4u32) % n;
+        let u3 = u.modpow(&BigUint::from(3u32), n);
+        let v3 = v.modpow(&BigUint::from(3u32), n);
+        let difference = (&v + n - &u) % n;
+        let numerator = difference.modpow(&BigUint::from(3u32), n) * ((&u * 3u32 + &v) % n) % n;
+        let denominator = (&u3 * &v * 16u32) % n;
+        match inverse(&denominator, n) {
+            Ok(inverse) => CurveSetup::Curve(
+                Curve {
+                    n,
+                    a24: numerator * inverse % n,
+                },
+                Point { x: u3, z: v3 },
+            ),
+            Err(g) if !g.is_one() && g != *n => CurveSetup::Factor(g),
+            Err(_) => CurveSetup::Failed,
+        }
+    }
+
+    fn sub(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        if a >= b {
+            a - b
+        } else {
+            self.n - (b - a)
+        }
+    }
+
+    /// 2P
+    fn double(&self, p: &Point) -> Point {
+        let n = self.n;
+        let sum = (&p.x + &p.z) % n;
+        let sum_squared = &sum * &sum % n;
+        let difference = self.sub(&p.x, &p.z);
+        let difference_squared = &difference * &difference % n;
+        // 4XZ = (X + Z)^2 - (X - Z)^2
+        let cross = self.sub(&sum_squared, &difference_squared);
+        Point {
+            x: &sum_squared * &difference_squared % n,
+            z: (&difference_squared + &self.a24 * &cross) % n * &cross % n,
+        }
+    }
+
+    /// P + Q, given P - Q
+    fn add(&self, p: &Point, q: &Point, difference: &Point) -> Point {
+        let n = self.n;
+        let u = self.sub(&p.x, &p.z) * ((&q.x + &q.z) % n) % n;
+        let v = ((&p.x + &p.z) % n) * self.sub(&q.x, &q.z) % n;
+        let sum = (&u + &v) % n;
+        let minus = self.sub(&u, &v);
+        Point {
+            x: &difference.z * (&sum * &sum % n) % n,
+            z: &difference.x * (&minus * &minus % n) % n,
+        }
+    }
+
+    /// kP with the Montgomery ladder, for k >= 1
+    fn multiply(&self, p: &Point, k: u64) -> Point {
+        let mut low = p.clone();
+        let mut high = self.double(p);
+        for bit in (0..63 - k.leading_zeros()).rev() {
+            if k >> bit & 1 == 1 {
+                low = self.add(&high, &low, p);
+                high = self.double(&high);
+            } else {
+                high = self.add(&high, &low, p);
+                low = self.double(&low);
+            }
+        }
+        low
+    }
+}
+
+/// Stage 1: multiply by the largest power of every prime up to B1
+fn stage_one(curve: &Curve, point: Point, b1: u64) -> Point {
+    segmented_sieve(2, b1).into_iter().fold(point, |point, p| {
+        let mut power = p;
+        while power <= b1 / p {
+            power *= p;
+        }
+        curve.multiply(&point, power)
+    })
+}
+
+/// Stage 2: accumulate the product of x(mD Q) - x(j Q) over every prime mD ± j in (B1, B2]
+///
+/// The difference vanishes modulo p when (mD ∓ j)Q is the identity, so a single prime factor of the
+/// remaining order in (B1, B2] is caught with one giant step per D and one multiplication per prime.
+fn stage_two(curve: &Curve, q: &Point, b1: u64, b2: u64) -> BigUint {
+    let n = curve.n;
+    let d = STAGE_TWO_STEP;
+    let half = d / 2;
+    let mut accumulator = BigUint::one();
+
+    // Baby steps jQ for odd j < D / 2 coprime to D, walking j -> j + 2 with the difference 2Q
+    let twice = curve.double(q);
+    let mut babies: Vec<Option<Point>> = vec![None; half as usize];
+    let (mut previous, mut current) = (q.clone(), curve.add(&twice, q, q));
+    babies[1] = Some(q.clone());
+    for j in (3..half).step_by(2) {
+        if j.gcd(&d) == 1 {
+            babies[j as usize] = Some(current.clone());
+        }
+        let next = curve.add(&current, &twice, &previous);
+        previous = std::mem::replace(&mut current, next);
+    }
+
+    // Giant steps mDQ, keeping (m + 1)DQ alongside so the next one is (m + 1)DQ + DQ with difference mDQ
+    let step = curve.multiply(q, d);
+    let mut giant: Option<(u64, Point, Point)> = None;
+    for p in segmented_sieve(b1 + 1, b2) {
+        let m = (p + half) / d;
+        if m == 0 {
+            // Primes below D / 2 have no giant step, multiply by them directly
+            accumulator = accumulator * curve.multiply(q, p).z % n;
+            continue;
+        }
+        let (index, g, following) = giant
+            .get_or_insert_with(|| (m, curve.multiply(q, m * d), curve.multiply(q, (m + 1) * d)));
+        while *index < m {
+            let next = curve.add(following, &step, g);
+            *g = std::mem::replace(following, next);
+            *index += 1;
+        }
+        let j = p.abs_diff(m * d);
+        let baby = babies[j as usize].as_ref().unwrap();
+        let cross = curve.sub(&(&g.x * &baby.z % n), &(&baby.x * &g.z % n));
+        accumulator = accumulator * cross % n;
+    }
+    accumulator
+}
+
+/// Lenstra's Elliptic Curve Method
+///
+/// Try up to config.curves random curves looking for a non-trivial factor of n, returning None when
+/// every curve fails. n should be composite and not a prime power of 2.
+pub fn ecm(n: &BigUint, config: &EcmConfig) -> Option<BigUint> {
+    if n.is_even() {
+        return if *n > BigUint::from(2u32) {
+            Some(BigUint::from(2u32))
+        } else {
+            None
+        };
+    }
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    for _ in 0..config.curves {
+        let sigma = rng.gen_range(6..u32::MAX as u64);
+        let (curve, point) = match Curve::suyama(n, sigma) {
+            CurveSetup::Curve(curve, point) => (curve, point),
+            CurveSetup::Factor(factor) => return Some(factor),
+            CurveSetup::Failed => continue,
+        };
+
+        let q = stage_one(&curve, point, config.b1);
+        let g = q.z.gcd(n);
+        if g == *n {
+            // Every prime of n divided the order at once, try another curve
+            continue;
+        } else if !g.is_one() {
+            return Some(g);
+        }
+
+        if config.b2 > config.b1 {
+            let g = stage_two(&curve, &q, config.b1, config.b2).gcd(n);
+            if !g.is_one() && g != *n {
+                return Some(g);
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num::Zero;
+    use rstest::rstest;
+
+    fn config(b1: u64, b2: u64) -> EcmConfig {
+        EcmConfig {
+            b1,
+            b2,
+            curves: 200,
+            seed: 1,
+        }
+    }
+
+    #[test]
+    fn ladder_test() {
+        // kP computed by the ladder agrees with repeated differential additions
+        let n = BigUint::from(1000003u64 * 1000033);
+        let (curve, p) = match Curve::suyama(&n, 11) {
+            CurveSetup::Curve(curve, p) => (curve, p),
+            _ => panic!("σ = 11 gives a curve"),
+        };
+        let x = |point: &Point| inverse(&point.z, &n).map(|inv| &point.x * inv % &n);
+        let (mut previous, mut current) = (p.clone(), curve.double(&p));
+        for k in 3..50u64 {
+            let next = curve.add(&current, &p, &previous);
+            previous = std::mem::replace(&mut current, next);
+            assert_eq!(x(&current), x(&curve.multiply(&p, k)));
+        }
+    }
+
+    #[rstest]
+    #[case(1000003, 1000033)]
+    #[case(999983, 1000000007)]
+    #[case(1000000007, 1000000009)]
+    fn ecm_test(#[case] p: u64, #[case] q: u64) {
+        let n = BigUint::from(p) * BigUint::from(q);
+        let factor = ecm(&n, &config(2000, 200000)).unwrap();
+        assert!(factor == BigUint::from(p) || factor == BigUint::from(q));
+    }
+
+    #[test]
+    fn ecm_stage_two_test() {
+        // Without stage 2 a tiny B1 rarely succeeds, with it the factor is found
+        let n = BigUint::from(1000003u64) * BigUint::from(1000000007u64);
+        let factor = ecm(&n, &config(100, 100000)).unwrap();
+        assert!(factor == BigUint::from(1000003u64) || factor == BigUint::from(1000000007u64));
+    }
+
+    #[test]
+    fn ecm_prime_power_test() {
+        let n = BigUint::from(1000003u64).pow(3);
+        let factor = ecm(&n, &config(2000, 200000)).unwrap();
+        assert_eq!(BigUint::zero(), &n % &factor);
+        assert!(factor > BigUint::one() && factor < n);
+    }
+
+    #[test]
+    fn ecm_prime_test() {
+        let config = EcmConfig {
+            curves: 3,
+            ..config(500, 5000)
+        };
+        assert_eq!(None, ecm(&BigUint::from(1000000007u64), &config));
+        assert_eq!(
+            Some(BigUint::from(2u32)),
+            ecm(&BigUint::from(1000000014u64), &config)
+        );
+    }
+}
@@ -1,9 +1,10 @@
 //! Euclidean Algorithms Traits
-use crate::general::numbers::Integer;
+use crate::general::numbers::{abs, Integer};
 
 /// Euclidean algorithm
 ///
 /// This trait contains the standard form of the euclidean algorithm. It is equivalent to the euclidean implementation.
+/// The result is always non-negative.
 pub trait Euclidean<T> {
     fn euclidean(self, other: T) -> T;
 }
@@ -22,13 +23,17 @@ macro_rules! euclidean {
                 let mut a = self;
                 let mut b = other;
                 while b != T::zero() {
+                    // Everything is divisible by 1 or -1, and T::min_value() % -1 overflows
+                    if b == T::one() || T::zero().checked_sub(&b) == Some(T::one()) {
+                        return T::one();
+                    }
                     let temp = b;
                     b = a % b;
                     a = temp;
                 }
 
-                // Return a
-                a
+                // The remainder takes the sign of the dividend, so a may be negative
+                abs(a)
             }
         }
     };
@@ -39,6 +44,7 @@ macro_rules! euclidean {
 /// Extended Euclidean algorithm
 ///
 /// Solves the equation ax + by = gcd(a, b) for x,y given a,b. Bezout's lemma guarantees solvability and x,y are called Bezout's coefficients.
+/// The gcd is always non-negative; the signs of the coefficients are adjusted to match.
 pub trait ExtendedEuclidean<T> {
     fn extended_euclidean(self, other: T) -> (T, T, T, T, T);
     fn egcd(self, other: T) -> (T, T, T);
@@ -71,6 +77,12 @@ macro_rules! extended_euclidean {
                     (prev_y, y) = (y, prev_y - q * y);
                 }
 
+                // Normalize to a non-negative gcd, negating the coefficients along with it
+                if prev_rem < T::zero() {
+                    (prev_rem, prev_x, prev_y) =
+                        (T::zero() - prev_rem, T::zero() - prev_x, T::zero() - prev_y);
+                }
+
                 // Return gcd, bezout_x, bezout_y, quotient_x, quotient_y
                 (prev_rem, prev_x, prev_y, x, y)
             }
@@ -115,6 +127,8 @@ mod tests {
     #[rstest]
     #[case(1073, 29, 29)]
     #[case(1073, 25, 1)]
+    #[case(-4, 6, 2)]
+    #[case(4, -6, 2)]
     fn isize_euclidean_test(#[case] a: isize, #[case] b: isize, #[case] expected: isize) {
         assert_eq!(expected, a.euclidean(b))
     }
@@ -122,6 +136,8 @@ mod tests {
     #[rstest]
     #[case(1073, 29, (29, 0, 1))]
     #[case(1073, 25, (1, 12, -515))]
+    #[case(4, -6, (2, -1, -1))]
+    #[case(-4, 6, (2, 1, 1))]
     fn isize_egcd_test(
         #[case] a: isize,
         #[case] b: isize,
@@ -129,4 +145,16 @@ mod tests {
     ) {
         assert_eq!(expected, a.egcd(b))
     }
+
+    #[test]
+    fn isize_egcd_negative_range_test() {
+        for a in -100isize..=100 {
+            for b in -100isize..=100 {
+                let (g, x, y) = a.egcd(b);
+                assert!(g >= 0);
+                assert_eq!(g, a.euclidean(b));
+                assert_eq!(g, a * x + b * y);
+            }
+        }
+    }
 }
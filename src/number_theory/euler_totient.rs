@@ -1,11 +1,16 @@
 //! Euler Totient Trait
 
-use crate::{general::numbers::Integer, number_theory::primality::Primality};
+use crate::{
+    general::numbers::{abs, Integer},
+    number_theory::primality::Primality,
+};
 
 /// Euler Totient Function
 ///
 /// Return the value of the Euler Totient Function:
 /// http://mathworld.wolfram.com/TotientFunction.html
+///
+/// The totient of a negative integer is the totient of its absolute value, and the totient of 0 is 0
 pub trait EulerTotient<T> {
     fn euler_totient(self) -> T;
     fn primality_checked_euler_totient(self) -> T;
@@ -16,11 +21,15 @@ macro_rules! euler_totient {
     ($t: ident) => {
         impl<T: $t> EulerTotient<T> for T {
             fn euler_totient(self) -> T {
+                let n = abs(self);
                 let one = T::one();
+                if n == one {
+                    return one;
+                }
                 let mut count = T::zero();
                 let mut i = one;
-                while i < self {
-                    if self.relative_primality(i) {
+                while i < n {
+                    if n.relative_primality(i) {
                         count += one;
                     }
                     i += one;
@@ -32,14 +41,17 @@ macro_rules! euler_totient {
             ///
             /// Results will be the same as for the standard totient, but I was curious about benchmarking for when primes may exist in the checked set of numbers
             fn primality_checked_euler_totient(self) -> T {
+                let n = abs(self);
                 let one = T::one();
-                if self.primality() {
-                    self - one
+                if n.primality() {
+                    n - one
+                } else if n == one {
+                    one
                 } else {
                     let mut count = T::zero();
                     let mut i = one;
-                    while i < self {
-                        if self.relative_primality(i) {
+                    while i < n {
+                        if n.relative_primality(i) {
                             count += one;
                         }
                         i += one;
@@ -59,6 +71,7 @@ mod tests {
     use rstest::rstest;
 
     #[rstest]
+    #[case(1, 1)]
     #[case(2, 1)]
     #[case(3, 2)]
     #[case(5, 4)]
@@ -69,15 +82,21 @@ mod tests {
     }
 
     #[rstest]
+    #[case(1, 1)]
     #[case(2, 1)]
     #[case(3, 2)]
     #[case(5, 4)]
     #[case(9, 6)]
     #[case(200, 80)]
+    #[case(0, 0)]
+    #[case(-1, 1)]
+    #[case(-9, 6)]
+    #[case(-200, 80)]
     fn isize_euler_totient_test(#[case] a: isize, #[case] expected: isize) {
         assert_eq!(expected, a.euler_totient())
     }
     #[rstest]
+    #[case(1, 1)]
     #[case(2, 1)]
     #[case(3, 2)]
     #[case(5, 4)]
@@ -88,11 +107,16 @@ mod tests {
     }
 
     #[rstest]
+    #[case(1, 1)]
     #[case(2, 1)]
     #[case(3, 2)]
     #[case(5, 4)]
     #[case(9, 6)]
     #[case(200, 80)]
+    #[case(0, 0)]
+    #[case(-1, 1)]
+    #[case(-9, 6)]
+    #[case(-200, 80)]
     fn isize_primality_checked_euler_totient_test(#[case] a: isize, #[case] expected: isize) {
         assert_eq!(expected, a.primality_checked_euler_totient())
     }
@@ -1,8 +1,11 @@
 //! Factorial Trait
-use crate::general::{errors::NumberTheoryErr, numbers::Integer};
-use std::fmt::Display;
+use crate::general::{
+    errors::{message, NumberTheoryErr},
+    numbers::Integer,
+};
+use core::fmt::Display;
 
-type Result<T> = std::result::Result<T, NumberTheoryErr>;
+type Result<T> = core::result::Result<T, NumberTheoryErr>;
 
 /// Implement the factorial operation
 pub trait Factorial<T = Self> {
@@ -13,8 +16,18 @@ pub trait Factorial<T = Self> {
 macro_rules! factorial {
     ($t: ident) => {
         impl<T: $t + Display> Factorial<T> for T {
+            /// Factorial
+            ///
+            /// The factorial is only defined for non-negative integers, negative inputs return a domain error
             #[inline]
             fn factorial(&self) -> Result<T> {
+                if *self < T::zero() {
+                    return Err(NumberTheoryErr::Domain(message!(
+                        "Factorial is undefined for negative integers: {}",
+                        self
+                    )));
+                }
+
                 let mut i = T::one();
                 let mut acc = T::one();
                 while i < *self {
@@ -22,7 +35,7 @@ macro_rules! factorial {
                     match acc.checked_mul(&i) {
                         Some(new) => acc = new,
                         None => {
-                            return Err(NumberTheoryErr::Overflow(format!(
+                            return Err(NumberTheoryErr::Overflow(message!(
                                 "Factorial overflow at: {}",
                                 acc
                             )))
@@ -58,6 +71,7 @@ mod tests {
     }
 
     #[rstest(
+        case::zero(0, 1),
         case::one(1, 1),
         case::two(2, 2),
         case::three(3, 6),
@@ -66,7 +80,9 @@ mod tests {
         case::twelve(12, 479001600),
         case::thirteen(13, 6227020800),
         #[should_panic]
-        case::fourteen(14, 14302774849602060)
+        case::fourteen(14, 14302774849602060),
+        #[should_panic]
+        case::negative(-1, 1)
     )]
     fn isize_factorial_test(#[case] a: isize, #[case] expected: isize) {
         // Unwrapping because must test guarantee this will not err on low values
@@ -0,0 +1,361 @@
+//! Big Integer Factorization
+//!
+//! Primality testing and prime factorization for BigUint, which is too large for the trial division
+//! in the Primality trait. Small primes are always divided out first, then composite cofactors are
+//! split with the chosen strategy until every factor is a probable prime.
+use crate::number_theory::{
+    ecm::{ecm, EcmConfig},
+    prime_counting::segmented_sieve,
+    quadratic_sieve::quadratic_sieve,
+};
+use num::{BigUint, Integer, One, Zero};
+
+/// Primes below this bound are divided out before any other strategy runs
+const SMALL_PRIME_BOUND: u64 = 1000;
+
+/// Composites above this many digits are handed to the quadratic sieve by the Auto strategy
+const QUADRATIC_SIEVE_DIGITS: u64 = 20;
+
+/// Steps of Pollard rho spent looking for a small factor before the quadratic sieve takes over
+const AUTO_RHO_STEPS: usize = 1 << 14;
+
+/// Miller-Rabin bases, the first 13 primes (up to 41) are a deterministic test below 3.3 * 10^24
+const WITNESSES: [u32; 20] = [
+    2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71,
+];
+
+/// How composite factors are split
+///
+/// - TrialDivision divides by every odd number up to the square root, only practical when the second
+///   largest prime factor is small
+/// - PollardRho uses Brent's variant, which finds a factor p in about √p steps
+/// - Ecm runs Lenstra's elliptic curve method, which is the better choice for factors of 15 digits and
+///   up. Pollard rho takes over if every curve fails, so the factorization is always complete.
+/// - QuadraticSieve runs the self-initializing quadratic sieve, whose running time depends only on the
+///   size of the composite. Pollard rho takes over if it fails.
+/// - Auto uses Pollard rho up to 20 digits. Larger composites get a short run of Pollard rho for small
+///   factors, then the quadratic sieve.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum FactorizationStrategy {
+    TrialDivision,
+    PollardRho,
+    Ecm(EcmConfig),
+    QuadraticSieve,
+    #[default]
+    Auto,
+}
+
+/// Big Integer Primality Trait
+///
+/// Probable primality and prime factorizations of arbitrarily large unsigned integers. The factors are
+/// returned in increasing order, with multiplicity, and their product is the input (the factorization
+/// of 0 is [0]).
+pub trait BigPrimality {
+    fn is_probable_prime(&self) -> bool;
+    fn prime_factorization(&self) -> Vec<BigUint>;
+    fn prime_factorization_with(&self, strategy: &FactorizationStrategy) -> Vec<BigUint>;
+}
+
+impl BigPrimality for BigUint {
+    /// Miller-Rabin
+    ///
+    /// Strong probable prime test to the first 20 prime bases, deterministic below 3.3 * 10^24
+    fn is_probable_prime(&self) -> bool {
+        let n = self;
+        if *n < BigUint::from(2u32) {
+            return false;
+        }
+        for p in WITNESSES {
+            if *n == BigUint::from(p) {
+                return true;
+            } else if (n % p).is_zero() {
+                return false;
+            }
+        }
+
+        // n - 1 = d * 2^s with d odd
+        let n_minus_one = n - 1u32;
+        let s = n_minus_one.trailing_zeros().unwrap();
+        let d = &n_minus_one >> s;
+        'witness: for a in WITNESSES {
+            let mut x = BigUint::from(a).modpow(&d, n);
+            if x.is_one() || x == n_minus_one {
+                continue;
+            }
+            for _ in 1..s {
+                x = &x * &x % n;
+                if x == n_minus_one {
+                    continue 'witness;
+                }
+            }
+            return false;
+        }
+        true
+    }
+
+    /// Prime factorization with the default strategy, Auto
+    fn prime_factorization(&self) -> Vec<BigUint> {
+        self.prime_factorization_with(&FactorizationStrategy::default())
+    }
+
+    /// Prime factorization with a chosen strategy for splitting composites
+    fn prime_factorization_with(&self, strategy: &FactorizationStrategy) -> Vec<BigUint> {
+        if self.is_zero() {
+            return vec![BigUint::zero()];
+        }
+
+        // Divide out the small primes
+        let mut n = self.clone();
+        let mut factors = vec![];
+        for p in segmented_sieve(2, SMALL_PRIME_BOUND) {
+            while (&n % p).is_zero() {
+                factors.push(BigUint::from(p));
+                n /= p;
+            }
+        }
+
+        // Split composites until only primes remain
+        let mut composites = vec![n];
+        while let Some(m) = composites.pop() {
+            if m.is_one() {
+                continue;
+            } else if m.is_probable_prime() {
+                factors.push(m);
+                continue;
+            }
+            let factor = match strategy {
+                FactorizationStrategy::TrialDivision => trial_division(&m),
+                FactorizationStrategy::PollardRho => pollard_rho(&m),
+                FactorizationStrategy::Ecm(config) => {
+                    ecm(&m, config).unwrap_or_else(|| pollard_rho(&m))
+                }
+                FactorizationStrategy::QuadraticSieve => {
+                    quadratic_sieve(&m).unwrap_or_else(|| pollard_rho(&m))
+                }
+                FactorizationStrategy::Auto => {
+                    if (m.bits() as f64 * 2f64.log10()) as u64 <= QUADRATIC_SIEVE_DIGITS {
+                        pollard_rho(&m)
+                    } else {
+                        brent(&m, 1, AUTO_RHO_STEPS)
+                            .or_else(|| quadratic_sieve(&m))
+                            .unwrap_or_else(|| pollard_rho(&m))
+                    }
+                }
+            };
+            composites.push(&m / &factor);
+            composites.push(factor);
+        }
+        factors.sort();
+        factors
+    }
+}
+
+/// Smallest odd factor of an odd composite
+fn trial_division(n: &BigUint) -> BigUint {
+    let mut d = BigUint::from(3u32);
+    while !(n % &d).is_zero() {
+        d += 2u32;
+    }
+    d
+}
+
+/// Pollard's Rho, Brent's Variant
+///
+/// A non-trivial factor of the odd composite n. Iterates x -> x^2 + c, comparing against the value saved
+/// at each power of two and batching the gcds over blocks of steps. A new c is tried whenever the cycle
+/// closes without splitting n.
+pub fn pollard_rho(n: &BigUint) -> BigUint {
+    if n.is_even() {
+        return BigUint::from(2u32);
+    }
+    (1u32..).find_map(|c| brent(n, c, usize::MAX)).unwrap()
+}
+
+/// One run of Brent's rho with x -> x^2 + c, giving up after roughly max_steps iterations
+///
+/// If a batch of gcds overshoots to n, the batch is retraced one step at a time. None when n was not
+/// split, either because the step limit was reached or because every prime of n was found at once.
+fn brent(n: &BigUint, c: u32, max_steps: usize) -> Option<BigUint> {
+    const BATCH: usize = 128;
+    let f = |x: &BigUint| (x * x + c) % n;
+    let mut y = BigUint::from(2u32);
+    let mut x = y.clone();
+    let mut saved = y.clone();
+    let mut g = BigUint::one();
+    let mut q = BigUint::one();
+    let mut r = 1;
+    while g.is_one() {
+        if r > max_steps {
+            return None;
+        }
+        x = y.clone();
+        for _ in 0..r {
+            y = f(&y);
+        }
+        let mut k = 0;
+        while k < r && g.is_one() {
+            saved = y.clone();
+            for _ in 0..BATCH.min(r - k) {
+                y = f(&y);
+                let difference = if x > y { &x - &y } else { &y - &x };
+                q = q * difference % n;
+            }
+            g = q.gcd(n);
+            k += BATCH;
+        }
+        r *= 2;
+    }
+    if g == *n {
+        // Retrace the last batch one gcd at a time
+        loop {
+            saved = f(&saved);
+            let difference = if x > saved { &x - &saved } else { &saved - &x };
+            g = difference.gcd(n);
+            if !g.is_one() {
+                break;
+            }
+        }
+    }
+    (g != *n).then_some(g)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn big(values: &[u64]) -> Vec<BigUint> {
+        values.iter().map(|v| BigUint::from(*v)).collect()
+    }
+
+    #[rstest]
+    #[case(0, false)]
+    #[case(1, false)]
+    #[case(2, true)]
+    #[case(71, true)]
+    #[case(561, false)]
+    #[case(1000000007, true)]
+    #[case(3215031751, false)]
+    #[case(18446744073709551557, true)]
+    #[case(18446744073709551559, false)]
+    fn u64_is_probable_prime_test(#[case] n: u64, #[case] expected: bool) {
+        assert_eq!(expected, BigUint::from(n).is_probable_prime())
+    }
+
+    #[test]
+    fn is_probable_prime_range_test() {
+        use crate::number_theory::primality::Primality;
+        for n in 0u64..5000 {
+            assert_eq!(n.primality(), BigUint::from(n).is_probable_prime());
+        }
+    }
+
+    #[test]
+    fn is_probable_prime_big_test() {
+        // 2^127 - 1 is a Mersenne prime, 2^128 + 1 is not
+        let one = BigUint::one();
+        assert!(((&one << 127u32) - 1u32).is_probable_prime());
+        assert!(!((&one << 128u32) + 1u32).is_probable_prime());
+    }
+
+    #[rstest]
+    #[case(0, vec![0])]
+    #[case(1, vec![])]
+    #[case(2, vec![2])]
+    #[case(360, vec![2, 2, 2, 3, 3, 5])]
+    #[case(1000003 * 1000033, vec![1000003, 1000033])]
+    #[case(1000003 * 1000003 * 999983, vec![999983, 1000003, 1000003])]
+    #[case(18446744073709551557, vec![18446744073709551557])]
+    fn u64_prime_factorization_test(#[case] n: u64, #[case] expected: Vec<u64>) {
+        let n = BigUint::from(n);
+        assert_eq!(big(&expected), n.prime_factorization());
+        assert_eq!(
+            big(&expected),
+            n.prime_factorization_with(&FactorizationStrategy::TrialDivision)
+        );
+        assert_eq!(
+            big(&expected),
+            n.prime_factorization_with(&FactorizationStrategy::PollardRho)
+        );
+        assert_eq!(
+            big(&expected),
+            n.prime_factorization_with(&FactorizationStrategy::Ecm(EcmConfig {
+                b1: 2000,
+                b2: 200000,
+                ..EcmConfig::default()
+            }))
+        );
+        assert_eq!(
+            big(&expected),
+            n.prime_factorization_with(&FactorizationStrategy::QuadraticSieve)
+        );
+    }
+
+    #[test]
+    fn fermat_number_factorization_test() {
+        // F6 = 2^64 + 1 = 274177 * 67280421310721
+        let n = (BigUint::one() << 64u32) + 1u32;
+        let expected = big(&[274177, 67280421310721]);
+        assert_eq!(expected, n.prime_factorization());
+        assert_eq!(
+            expected,
+            n.prime_factorization_with(&FactorizationStrategy::Ecm(EcmConfig {
+                b1: 2000,
+                b2: 200000,
+                ..EcmConfig::default()
+            }))
+        );
+    }
+
+    #[test]
+    fn auto_factorization_test() {
+        // 2^128 + 1 = 59649589127497217 * 5704689200685129054721 is beyond Pollard rho, the
+        // quadratic sieve splits it
+        let n = (BigUint::one() << 128u32) + 1u32;
+        let expected = vec![
+            BigUint::from(59649589127497217u64),
+            "5704689200685129054721".parse().unwrap(),
+        ];
+        assert_eq!(expected, n.prime_factorization());
+    }
+
+    #[test]
+    fn ecm_fallback_test() {
+        // A single tiny curve cannot split this, Pollard rho finishes the job
+        let strategy = FactorizationStrategy::Ecm(EcmConfig {
+            b1: 10,
+            b2: 10,
+            curves: 1,
+            seed: 0,
+        });
+        let n = BigUint::from(1000003u64) * BigUint::from(1000033u64);
+        assert_eq!(
+            big(&[1000003, 1000033]),
+            n.prime_factorization_with(&strategy)
+        );
+    }
+
+    #[rstest]
+    #[case(8051)]
+    #[case(1000003 * 1000033)]
+    #[case(999983 * 999983)]
+    fn pollard_rho_test(#[case] n: u64) {
+        let n = BigUint::from(n);
+        let factor = pollard_rho(&n);
+        assert!(factor > BigUint::one() && factor < n);
+        assert!((&n % &factor).is_zero());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn factorization_serde_test() {
+        // Factorizations are plain vectors, BigUint serializes through num's serde feature
+        let n = BigUint::from(1000003u64 * 1000033) * 12u32;
+        let factors = n.prime_factorization();
+        let json = serde_json::to_string(&factors).unwrap();
+        assert_eq!(
+            factors,
+            serde_json::from_str::<Vec<BigUint>>(&json).unwrap()
+        );
+    }
+}
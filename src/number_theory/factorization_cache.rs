@@ -0,0 +1,289 @@
+//! Factorization Cache: prime factorizations persisted to a file
+//!
+//! Factoring the same large numbers again in every run is wasteful, so the cache keeps one line per
+//! number in an append-only text file:
+//!
+//! 8051: 83 97
+//!
+//! The file is read once when the cache is opened and new factorizations are appended as they are
+//! computed, so several runs can share it and an interrupted write only loses its own line. Entries are
+//! not trusted: every cached factorization must be sorted, consist of primes apart from a leading -1,
+//! and multiply back to the number before it is returned, and one that does not (a corrupted or hand
+//! edited line) is recomputed and appended again. When a number appears on several lines the last one wins.
+use crate::{
+    general::numbers::Integer,
+    number_theory::{
+        factorization::{BigPrimality, FactorizationStrategy},
+        primality::Primality,
+    },
+};
+use num::{BigUint, One, Zero};
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    fs::OpenOptions,
+    io::{self, Read, Write},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+/// Factorization Cache
+///
+/// Prime factorizations of machine integers through Primality and of BigUint through BigPrimality,
+/// keyed by the decimal representation of the number so both share the same file
+#[derive(Debug)]
+pub struct FactorizationCache {
+    path: PathBuf,
+    entries: HashMap<String, Vec<String>>,
+    /// The file ends in the middle of a line, left by an interrupted write
+    partial_line: bool,
+}
+
+impl FactorizationCache {
+    /// Open the cache file at path, creating it if it does not exist. Lines that cannot be parsed are
+    /// skipped.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let mut contents = String::new();
+        OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(&path)?
+            .read_to_string(&mut contents)?;
+        let mut entries = HashMap::new();
+        for line in contents.lines() {
+            if let Some((n, factors)) = line.split_once(':') {
+                let n = n.trim();
+                if !n.is_empty() {
+                    let factors = factors.split_whitespace().map(String::from).collect();
+                    entries.insert(n.to_string(), factors);
+                }
+            }
+        }
+        Ok(FactorizationCache {
+            path,
+            entries,
+            partial_line: !contents.is_empty() && !contents.ends_with('\n'),
+        })
+    }
+
+    /// The number of cached factorizations
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Primality::prime_factorization through the cache
+    pub fn prime_factorization<T>(&mut self, n: T) -> io::Result<Vec<T>>
+    where
+        T: Integer + Display + FromStr,
+    {
+        let key = n.to_string();
+        let valid = |factors: &[T]| {
+            if n == T::zero() {
+                return factors == [T::zero()];
+            }
+            // Only a signed factorization may start with -1
+            let primes = match factors.split_first() {
+                Some((first, rest))
+                    if T::min_value() < T::zero() && *first == T::zero() - T::one() =>
+                {
+                    rest
+                }
+                _ => factors,
+            };
+            // Miller-Rabin on the widened factor, trial division would cost as much as factoring again
+            sorted_primes(primes, |p| {
+                p.to_u128()
+                    .is_some_and(|p| BigUint::from(p).is_probable_prime())
+            }) && factors
+                .iter()
+                .try_fold(T::one(), |product, factor| product.checked_mul(factor))
+                == Some(n)
+        };
+        if let Some(factors) = self.cached(&key, valid) {
+            return Ok(factors);
+        }
+        let factors = n.prime_factorization();
+        self.insert(key, &factors)?;
+        Ok(factors)
+    }
+
+    /// BigPrimality::prime_factorization through the cache
+    pub fn big_prime_factorization(&mut self, n: &BigUint) -> io::Result<Vec<BigUint>> {
+        self.big_prime_factorization_with(n, &FactorizationStrategy::default())
+    }
+
+    /// BigPrimality::prime_factorization_with through the cache, the strategy is only used on a miss
+    pub fn big_prime_factorization_with(
+        &mut self,
+        n: &BigUint,
+        strategy: &FactorizationStrategy,
+    ) -> io::Result<Vec<BigUint>> {
+        let key = n.to_string();
+        let valid = |factors: &[BigUint]| {
+            if n.is_zero() {
+                return factors == [BigUint::zero()];
+            }
+            sorted_primes(factors, BigUint::is_probable_prime)
+                && factors.iter().fold(BigUint::one(), |p, f| p * f) == *n
+        };
+        if let Some(factors) = self.cached(&key, valid) {
+            return Ok(factors);
+        }
+        let factors = n.prime_factorization_with(strategy);
+        self.insert(key, &factors)?;
+        Ok(factors)
+    }
+
+    /// The cached factors of key, if every one parses and they pass the check
+    fn cached<T: FromStr>(&self, key: &str, check: impl Fn(&[T]) -> bool) -> Option<Vec<T>> {
+        let factors = self
+            .entries
+            .get(key)?
+            .iter()
+            .map(|factor| factor.parse().ok())
+            .collect::<Option<Vec<T>>>()?;
+        check(&factors).then_some(factors)
+    }
+
+    /// Record a factorization in memory and append it to the file as a single write
+    fn insert<T: Display>(&mut self, key: String, factors: &[T]) -> io::Result<()> {
+        let factors: Vec<String> = factors.iter().map(|factor| factor.to_string()).collect();
+        let mut line = format!("{}: {}\n", key, factors.join(" "));
+        if self.partial_line {
+            line.insert(0, '\n');
+        }
+        OpenOptions::new()
+            .append(true)
+            .open(&self.path)?
+            .write_all(line.as_bytes())?;
+        self.partial_line = false;
+        self.entries.insert(key, factors);
+        Ok(())
+    }
+}
+
+/// Factors in non-decreasing order that all pass the primality test
+fn sorted_primes<T: Ord>(factors: &[T], is_prime: impl Fn(&T) -> bool) -> bool {
+    factors.windows(2).all(|pair| pair[0] <= pair[1]) && factors.iter().all(is_prime)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    /// A fresh cache file in the temporary directory for each test
+    fn cache_path(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "factorization_cache_{}_{}",
+            std::process::id(),
+            name
+        ));
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    fn line_count(path: &Path) -> usize {
+        fs::read_to_string(path).unwrap().lines().count()
+    }
+
+    #[test]
+    fn persistence_test() {
+        let path = cache_path("persistence");
+        let mut cache = FactorizationCache::open(&path).unwrap();
+        assert!(cache.is_empty());
+        for n in [-360i64, 0, 1, 8051, 1 << 40, i64::MIN] {
+            assert_eq!(
+                n.prime_factorization(),
+                cache.prime_factorization(n).unwrap()
+            );
+        }
+        assert_eq!(6, line_count(&path));
+
+        // A second run reads every entry back and appends nothing
+        let mut cache = FactorizationCache::open(&path).unwrap();
+        assert_eq!(6, cache.len());
+        assert_eq!(vec![83, 97], cache.prime_factorization(8051u32).unwrap());
+        assert_eq!(
+            vec![-1, 2, 2, 2, 3, 3, 5],
+            cache.prime_factorization(-360i16).unwrap()
+        );
+        assert_eq!(Vec::<u8>::new(), cache.prime_factorization(1u8).unwrap());
+        assert_eq!(6, line_count(&path));
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn big_persistence_test() {
+        let path = cache_path("big");
+        let n: BigUint = "1000000016000000063".parse().unwrap();
+        let expected = n.prime_factorization();
+        let mut cache = FactorizationCache::open(&path).unwrap();
+        assert_eq!(expected, cache.big_prime_factorization(&n).unwrap());
+
+        // Machine integers and BigUint share the entries
+        let mut cache = FactorizationCache::open(&path).unwrap();
+        assert_eq!(expected, cache.big_prime_factorization(&n).unwrap());
+        assert_eq!(
+            vec![1000000007u64, 1000000009],
+            cache.prime_factorization(1000000016000000063u64).unwrap()
+        );
+        assert_eq!(1, line_count(&path));
+
+        // A composite factor is caught by the probable prime test
+        fs::write(&path, "221: 221\n").unwrap();
+        let mut cache = FactorizationCache::open(&path).unwrap();
+        assert_eq!(
+            vec![BigUint::from(13u32), BigUint::from(17u32)],
+            cache
+                .big_prime_factorization(&BigUint::from(221u32))
+                .unwrap()
+        );
+        assert_eq!(2, line_count(&path));
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn verification_test() {
+        let path = cache_path("verification");
+        fs::write(
+            &path,
+            "8051: 83 98\n360: 2 2 2 3 3 5\nnot an entry\n77: 7 x\n12: 3 4\n15: 15\n21: 1 3 7\n\
+             35: 7 5\n-6: 2 -1 3\n100000000",
+        )
+        .unwrap();
+        let mut cache = FactorizationCache::open(&path).unwrap();
+        assert_eq!(8, cache.len());
+
+        // The unfinished last line is left alone, and entries that do not multiply back or do not
+        // parse are recomputed and appended again
+        assert_eq!(vec![83, 97], cache.prime_factorization(8051).unwrap());
+        assert_eq!(vec![7, 11], cache.prime_factorization(77).unwrap());
+        assert_eq!(
+            vec![2, 2, 2, 3, 3, 5],
+            cache.prime_factorization(360).unwrap()
+        );
+        assert_eq!(12, line_count(&path));
+
+        // Composite, unit or unsorted factors are recomputed even when the product matches
+        assert_eq!(vec![2, 2, 3], cache.prime_factorization(12).unwrap());
+        assert_eq!(vec![3, 5], cache.prime_factorization(15).unwrap());
+        assert_eq!(vec![3, 7], cache.prime_factorization(21).unwrap());
+        assert_eq!(vec![5, 7], cache.prime_factorization(35).unwrap());
+        assert_eq!(vec![-1, 2, 3], cache.prime_factorization(-6).unwrap());
+        assert_eq!(17, line_count(&path));
+
+        // The last line for a number wins
+        let mut cache = FactorizationCache::open(&path).unwrap();
+        assert_eq!(vec![83, 97], cache.prime_factorization(8051).unwrap());
+        assert_eq!(vec![2, 2, 3], cache.prime_factorization(12).unwrap());
+        assert_eq!(17, line_count(&path));
+        fs::remove_file(&path).unwrap();
+    }
+}
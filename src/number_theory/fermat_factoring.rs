@@ -0,0 +1,177 @@
+//! Difference of Squares Factoring: Fermat's method and Hart's one line factorization
+//!
+//! Both look for x^2 - y^2 = (x - y)(x + y) multiples of n. Fermat's method splits n = pq in a single
+//! step once (p + q) / 2 is reached from √n, so it is instant when p and q are close together, which
+//! is why RSA key generation rejects primes that share their leading bits. Hart's variant searches
+//! the multiples n, 2n, 3n, ... instead and takes about n^(1/3) steps whatever the factors look like.
+use crate::{
+    general::numbers::{abs, Integer},
+    number_theory::{
+        gcd::Gcd, integer_roots::IntegerRoots, modular_exponentiation::ModularExponentiation,
+    },
+};
+
+/// Fermat Factoring Trait
+///
+/// A non-trivial factor of |self|, or None when none was found within max_steps steps. Even
+/// composites give 2 straight away, and None is also returned when a step would overflow T.
+pub trait FermatFactoring<T> {
+    fn fermat_factor(self, max_steps: u64) -> Option<T>;
+    fn hart_factor(self, max_steps: u64) -> Option<T>;
+}
+
+#[macro_export]
+macro_rules! fermat_factoring {
+    ($t: ident) => {
+        impl<T> FermatFactoring<T> for T
+        where
+            T: $t,
+        {
+            /// Fermat's Factorization
+            ///
+            /// Steps x up from ⌈√n⌉ until x^2 - n = y^2, then returns the smaller factor x - y. The
+            /// residual x^2 - n is updated by adding 2x + 1, so no square is formed after the first.
+            fn fermat_factor(self, max_steps: u64) -> Option<T> {
+                let n = abs(self);
+                let one = T::one();
+                let two = one + one;
+                if n <= two + one {
+                    return None;
+                } else if n % two == T::zero() {
+                    return Some(two);
+                }
+
+                let mut x = n.isqrt();
+                if x * x != n {
+                    x += one;
+                }
+                let mut residual = x.checked_mul(&x)? - n;
+                for _ in 0..max_steps {
+                    if residual.is_perfect_square() {
+                        let factor = x - residual.isqrt();
+                        // x = (n + 1) / 2 only gives the trivial 1 * n, n is prime
+                        return (factor != one).then_some(factor);
+                    }
+                    residual = residual.checked_add(&(x + x + one))?;
+                    x += one;
+                }
+                None
+            }
+
+            /// Hart's One Line Factorization
+            ///
+            /// For i = 1, 2, ... takes s = ⌈√(in)⌉ and checks whether s^2 mod n is a square t^2, in
+            /// which case gcd(s - t, n) is usually a factor. The search misses factors below n^(1/3), so
+            /// those are trial divided first as Hart recommends.
+            fn hart_factor(self, max_steps: u64) -> Option<T> {
+                let n = abs(self);
+                let one = T::one();
+                let two = one + one;
+                if n <= two + one {
+                    return None;
+                } else if n % two == T::zero() {
+                    return Some(two);
+                }
+
+                let mut d = two + one;
+                while d <= n / d / d {
+                    if n % d == T::zero() {
+                        return Some(d);
+                    }
+                    d += two;
+                }
+
+                let mut multiple = T::zero();
+                for _ in 0..max_steps {
+                    multiple = multiple.checked_add(&n)?;
+                    let mut s = multiple.isqrt();
+                    if s * s != multiple {
+                        s += one;
+                    }
+                    let residue = s.mod_mul(s, n);
+                    if residue.is_perfect_square() {
+                        let factor = (s - residue.isqrt()).gcd(n);
+                        if factor != one && factor != n {
+                            return Some(factor);
+                        }
+                    }
+                }
+                None
+            }
+        }
+    };
+}
+
+fermat_factoring!(Integer);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(5959, 59)]
+    #[case(1000003 * 1000033, 1000003)]
+    #[case(999983 * 999983, 999983)]
+    #[case(15, 3)]
+    #[case(-8051, 83)]
+    #[case(1000, 2)]
+    fn i64_fermat_factor_test(#[case] n: i64, #[case] expected: i64) {
+        assert_eq!(Some(expected), n.fermat_factor(100))
+    }
+
+    #[test]
+    fn u64_fermat_factor_near_square_test() {
+        // (2^32 - 17)(2^32 - 5) is close to u64::MAX and splits on the first step
+        let n = 4294967279u64 * 4294967291;
+        assert_eq!(Some(4294967279), n.fermat_factor(1));
+        assert_eq!(Some(4294967279), n.hart_factor(1));
+    }
+
+    #[test]
+    fn fermat_factor_far_apart_test() {
+        // 3 * 1000003 needs about 500000 steps
+        assert_eq!(None, (3u64 * 1000003).fermat_factor(1000));
+        assert_eq!(Some(3), (3u64 * 1000003).fermat_factor(1000000));
+    }
+
+    #[rstest]
+    #[case(8051)]
+    #[case(10403)]
+    #[case(1000003 * 1000033)]
+    #[case(3 * 1000003)]
+    #[case(1009 * 1000003)]
+    #[case(-(999983 * 999983))]
+    fn i64_hart_factor_test(#[case] n: i64) {
+        let factor = n.hart_factor(100000).unwrap();
+        assert!(factor > 1 && factor < n.abs() && n % factor == 0);
+    }
+
+    #[rstest]
+    #[case(1)]
+    #[case(3)]
+    #[case(101)]
+    #[case(1000003)]
+    fn i64_prime_test(#[case] p: i64) {
+        assert_eq!(None, p.fermat_factor(1000000));
+        assert_eq!(None, p.hart_factor(1000));
+    }
+
+    #[test]
+    fn u32_range_test() {
+        use crate::number_theory::primality::Primality;
+        for n in 4u32..2000 {
+            let fermat = n.fermat_factor(1000);
+            let hart = n.hart_factor(1000);
+            if n.primality() {
+                assert_eq!(None, fermat);
+            } else {
+                let factor = fermat.unwrap();
+                assert!(factor > 1 && factor < n && n % factor == 0);
+            }
+            if let Some(factor) = hart {
+                assert!(factor > 1 && factor < n && n % factor == 0);
+            }
+        }
+    }
+}
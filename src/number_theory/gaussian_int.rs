@@ -0,0 +1,580 @@
+//! Gaussian Integers: elements of ℤ[i]
+use crate::{
+    general::{
+        errors::{message, NumberTheoryErr},
+        numbers::{abs, Integer},
+    },
+    number_theory::{
+        euclidean_algs::Euclidean, integer_roots::IntegerRoots, primality::Primality,
+        quadratic_residue::QuadraticResidue,
+    },
+};
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
+use core::{
+    any::type_name,
+    fmt,
+    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, RemAssign, Sub, SubAssign},
+};
+
+type Result<T> = core::result::Result<T, NumberTheoryErr>;
+
+/// Gaussian Integer
+///
+/// re + im·i with integer components. ℤ[i] is a Euclidean domain under the norm re² + im², so it has
+/// division with remainder, greatest common divisors and unique factorization into Gaussian primes up
+/// to the units 1, i, -1 and -i.
+///
+/// Arithmetic operators behave like the integer operators of T: they overflow when a component is not
+/// representable, and division by zero panics. Almost every operation produces negative components, so
+/// T is meant to be a signed type.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct GaussianInt<T> {
+    pub re: T,
+    pub im: T,
+}
+
+/// a / n rounded to the nearest integer for n > 0, ties away from zero
+fn round_div<T: Integer>(a: T, n: T) -> T {
+    let zero = T::zero();
+    let (q, r) = (a / n, a % n);
+    if r > zero && n - r <= r {
+        q + T::one()
+    } else if r < zero && n + r <= zero - r {
+        q - T::one()
+    } else {
+        q
+    }
+}
+
+impl<T: Integer> GaussianInt<T> {
+    /// Create re + im·i
+    pub fn new(re: T, im: T) -> Self {
+        Self { re, im }
+    }
+
+    /// Zero
+    pub fn zero() -> Self {
+        Self::from(T::zero())
+    }
+
+    /// One
+    pub fn one() -> Self {
+        Self::from(T::one())
+    }
+
+    /// The imaginary unit i
+    pub fn i() -> Self {
+        Self::new(T::zero(), T::one())
+    }
+
+    /// The complex conjugate re - im·i
+    pub fn conj(self) -> Self {
+        Self::new(self.re, T::zero() - self.im)
+    }
+
+    /// The norm re² + im², which is multiplicative: N(ab) = N(a)N(b)
+    pub fn norm(self) -> T {
+        self.re * self.re + self.im * self.im
+    }
+
+    /// The norm, or None when it is not representable
+    pub fn checked_norm(self) -> Option<T> {
+        self.re
+            .checked_mul(&self.re)?
+            .checked_add(&self.im.checked_mul(&self.im)?)
+    }
+
+    /// Check if the element is one of the units 1, i, -1 and -i
+    pub fn is_unit(self) -> bool {
+        self.norm() == T::one()
+    }
+
+    /// The associate in the first quadrant, re > 0 and im >= 0, which is how gcds and prime factors are
+    /// reported. Zero is its own only associate.
+    pub fn normalize(self) -> Self {
+        let zero = T::zero();
+        let mut z = self;
+        if z == Self::zero() {
+            return z;
+        }
+
+        // Multiplying by i rotates a quarter turn, at most three are needed
+        while !(z.re > zero && z.im >= zero) {
+            z = Self::new(zero - z.im, z.re);
+        }
+        z
+    }
+
+    /// Exponentiation by squaring
+    pub fn pow(self, mut exp: u32) -> Self {
+        let mut base = self;
+        let mut acc = Self::one();
+        while exp > 0 {
+            if exp & 1 == 1 {
+                acc *= base;
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = base * base;
+            }
+        }
+        acc
+    }
+
+    /// Euclidean division
+    ///
+    /// The quotient is self / other rounded to the nearest Gaussian integer, which leaves a remainder
+    /// with N(r) <= N(other) / 2 and self = q·other + r. Panics when other is zero.
+    pub fn div_rem(self, other: Self) -> (Self, Self) {
+        let n = other.norm();
+        let numerator = self * other.conj();
+        let q = Self::new(round_div(numerator.re, n), round_div(numerator.im, n));
+        (q, self - q * other)
+    }
+
+    /// Greatest Common Divisor through the Euclidean trait, normalized to the first quadrant
+    pub fn gcd(self, other: Self) -> Self {
+        self.euclidean(other)
+    }
+
+    /// Gaussian primality
+    ///
+    /// a + bi is a Gaussian prime when its norm is a rational prime, or when it is an associate of a
+    /// rational prime p = 3 (mod 4).
+    pub fn primality(self) -> bool {
+        let zero = T::zero();
+        let four = T::from_u8(4).unwrap();
+        let three = T::from_u8(3).unwrap();
+        if self.re == zero || self.im == zero {
+            let p = abs(self.re + self.im);
+            return p % four == three && p.primality();
+        }
+        self.checked_norm().is_some_and(|n| n.primality())
+    }
+
+    /// Factorization into Gaussian primes
+    ///
+    /// Every factor is a first quadrant Gaussian prime, ordered by the rational prime below it and preceded
+    /// by a unit when the product would otherwise be off by one. The product of the factors is always
+    /// the input, so the factorization of a unit is itself (or [] for 1) and the factorization of 0 is
+    /// [0].
+    ///
+    /// The norm is factored over the integers, and each rational prime p it contains corresponds to:
+    /// 1 + i for p = 2, p itself for p = 3 (mod 4), and one of a ± bi for p = a² + b² = 1 (mod 4), with
+    /// a and b found by Cornacchia's algorithm. Returns an Overflow error when the norm is not
+    /// representable.
+    #[cfg(feature = "alloc")]
+    pub fn prime_factorization(self) -> Result<Vec<Self>> {
+        let one = T::one();
+        let two = one + one;
+        let four = two + two;
+        if self == Self::zero() {
+            return Ok(vec![self]);
+        }
+        let norm = self.checked_norm().ok_or_else(|| {
+            NumberTheoryErr::Overflow(message!(
+                "The norm of a Gaussian integer overflows {}",
+                type_name::<T>()
+            ))
+        })?;
+
+        let mut rest = self;
+        let mut factors = vec![];
+        for p in norm.prime_factorization() {
+            let candidates = if p == two {
+                [Self::new(one, one), Self::new(one, one)]
+            } else if p % four == one {
+                let (a, b) = cornacchia(one, p)?;
+                [Self::new(a, b), Self::new(b, a)]
+            } else {
+                // p is inert and its norm p² takes up two copies of p, the second finds nothing left
+                [Self::from(p), Self::from(p)]
+            };
+            if let Some(prime) = candidates
+                .into_iter()
+                .find(|&prime| rest % prime == Self::zero())
+            {
+                rest /= prime;
+                factors.push(prime);
+            }
+        }
+
+        // Only a unit is left over
+        if rest != Self::one() {
+            factors.insert(0, rest);
+        }
+        Ok(factors)
+    }
+}
+
+/// Cornacchia's Algorithm
+///
+/// Solve x² + d·y² = p for a prime p and 0 < d < p, returning the solution with x, y >= 0. Starting
+/// from a square root r of -d modulo p, the Euclidean algorithm on p and r is run until the remainder
+/// drops below √p, which is then x when (p - x²) / d is a perfect square. NoSolution is returned
+/// otherwise, and a Domain error for d outside (0, p) or a modulus the square root finds composite.
+///
+/// With d = 1 this writes every prime p = 1 (mod 4) as a sum of two squares.
+pub fn cornacchia<T: Integer>(d: T, p: T) -> Result<(T, T)> {
+    let zero = T::zero();
+    if d <= zero || d >= p {
+        return Err(NumberTheoryErr::Domain(message!(
+            "Cornacchia's algorithm in {} requires 0 < d < p",
+            type_name::<T>()
+        )));
+    }
+    let no_solution = || {
+        NumberTheoryErr::NoSolution(message!(
+            "The prime is not of the form x² + dy² in {}",
+            type_name::<T>()
+        ))
+    };
+    let r = (p - d).modular_sqrt(p).map_err(|err| match err {
+        NumberTheoryErr::NoSolution(_) => no_solution(),
+        err => err,
+    })?;
+
+    // b² >= p exactly when b > ⌊√p⌋, p is prime and not a perfect square
+    let limit = p.isqrt();
+    let (mut a, mut b) = (p, r);
+    while b > limit {
+        (a, b) = (b, a % b);
+    }
+    let rest = p - b * b;
+    if rest % d != zero {
+        return Err(no_solution());
+    }
+    let y_squared = rest / d;
+    let y = y_squared.isqrt();
+    if y * y != y_squared {
+        return Err(no_solution());
+    }
+    Ok((b, y))
+}
+
+impl<T: Integer> Euclidean<GaussianInt<T>> for GaussianInt<T> {
+    /// Greatest Common Divisor
+    ///
+    /// The Euclidean algorithm with Euclidean division, normalized to the first quadrant since the gcd
+    /// is only defined up to a unit
+    fn euclidean(self, other: GaussianInt<T>) -> GaussianInt<T> {
+        let mut a = self;
+        let mut b = other;
+        while b != Self::zero() {
+            let temp = b;
+            b = a % b;
+            a = temp;
+        }
+        a.normalize()
+    }
+}
+
+impl<T: Integer> From<T> for GaussianInt<T> {
+    fn from(n: T) -> Self {
+        Self::new(n, T::zero())
+    }
+}
+
+impl<T: Integer + fmt::Display> fmt::Display for GaussianInt<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{:+}i", self.re, self.im)
+    }
+}
+
+impl<T: Integer> Add for GaussianInt<T> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        Self::new(self.re + rhs.re, self.im + rhs.im)
+    }
+}
+
+impl<T: Integer> Sub for GaussianInt<T> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        Self::new(self.re - rhs.re, self.im - rhs.im)
+    }
+}
+
+impl<T: Integer> Mul for GaussianInt<T> {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        Self::new(
+            self.re * rhs.re - self.im * rhs.im,
+            self.re * rhs.im + self.im * rhs.re,
+        )
+    }
+}
+
+impl<T: Integer> Div for GaussianInt<T> {
+    type Output = Self;
+    fn div(self, rhs: Self) -> Self {
+        self.div_rem(rhs).0
+    }
+}
+
+impl<T: Integer> Rem for GaussianInt<T> {
+    type Output = Self;
+    fn rem(self, rhs: Self) -> Self {
+        self.div_rem(rhs).1
+    }
+}
+
+impl<T: Integer> Neg for GaussianInt<T> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self::new(T::zero() - self.re, T::zero() - self.im)
+    }
+}
+
+/// Assignment operators defer to the binary operators
+macro_rules! gaussian_int_assign_ops {
+    ($($trait: ident, $method: ident, $op: tt);*) => {
+        $(
+            impl<T: Integer> $trait for GaussianInt<T> {
+                fn $method(&mut self, rhs: Self) {
+                    *self = *self $op rhs;
+                }
+            }
+        )*
+    };
+}
+
+gaussian_int_assign_ops!(
+    AddAssign, add_assign, +;
+    SubAssign, sub_assign, -;
+    MulAssign, mul_assign, *;
+    DivAssign, div_assign, /;
+    RemAssign, rem_assign, %
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn g(re: i64, im: i64) -> GaussianInt<i64> {
+        GaussianInt::new(re, im)
+    }
+
+    #[test]
+    fn gaussian_int_arithmetic_test() {
+        let a = g(3, 4);
+        let b = g(1, -2);
+        assert_eq!(g(4, 2), a + b);
+        assert_eq!(g(2, 6), a - b);
+        assert_eq!(g(11, -2), a * b);
+        assert_eq!(g(-3, -4), -a);
+        assert_eq!(g(3, -4), a.conj());
+        assert_eq!(g(-1, 0), GaussianInt::i() * GaussianInt::i());
+        assert_eq!(g(-7, 24), a.pow(2));
+        assert_eq!(g(1, 0), a.pow(0));
+        assert_eq!(g(-4, 0), g(1, 1).pow(4));
+
+        let mut c = a;
+        c *= b;
+        c += g(1, 1);
+        c -= g(2, 0);
+        assert_eq!(g(10, -1), c);
+    }
+
+    #[rstest]
+    #[case(g(3, 4), 25)]
+    #[case(g(-5, 0), 25)]
+    #[case(g(0, 0), 0)]
+    #[case(g(1, -1), 2)]
+    fn gaussian_int_norm_test(#[case] z: GaussianInt<i64>, #[case] expected: i64) {
+        assert_eq!(expected, z.norm());
+        assert_eq!(Some(expected), z.checked_norm());
+    }
+
+    #[test]
+    fn gaussian_int_norm_overflow_test() {
+        assert_eq!(None, GaussianInt::new(i32::MAX, 1).checked_norm());
+        assert_eq!(Some(25), GaussianInt::new(3i8, 4).checked_norm());
+    }
+
+    #[test]
+    fn gaussian_int_div_rem_test() {
+        // The remainder is at most half the norm of the divisor, for every quadrant and rounding case
+        for re in -12..=12 {
+            for im in -12..=12 {
+                for (c, d) in [(3, 1), (-2, 5), (0, 4), (1, 1), (-7, -3), (1, 0)] {
+                    let (a, b) = (g(re, im), g(c, d));
+                    let (q, r) = a.div_rem(b);
+                    assert_eq!(a, q * b + r);
+                    assert!(2 * r.norm() <= b.norm());
+                    assert_eq!(q, a / b);
+                    assert_eq!(r, a % b);
+                }
+            }
+        }
+        assert_eq!((g(1, -2), g(0, 0)), g(11, -2).div_rem(g(3, 4)));
+    }
+
+    #[test]
+    #[should_panic]
+    fn gaussian_int_div_zero_test() {
+        let _ = g(1, 1) / g(0, 0);
+    }
+
+    #[rstest]
+    #[case(g(3, 4), g(3, 4))]
+    #[case(g(-4, 3), g(3, 4))]
+    #[case(g(-3, -4), g(3, 4))]
+    #[case(g(0, -2), g(2, 0))]
+    #[case(g(0, 0), g(0, 0))]
+    fn gaussian_int_normalize_test(
+        #[case] z: GaussianInt<i64>,
+        #[case] expected: GaussianInt<i64>,
+    ) {
+        assert_eq!(expected, z.normalize())
+    }
+
+    #[rstest]
+    #[case(g(11, 3), g(1, 8), g(2, 1))]
+    #[case(g(4, 2), g(6, 0), g(2, 0))]
+    #[case(g(5, 0), g(3, 0), g(1, 0))]
+    #[case(g(0, 0), g(0, -3), g(3, 0))]
+    #[case(g(0, 0), g(0, 0), g(0, 0))]
+    #[case(g(2, 0), g(1, 1), g(1, 1))]
+    fn gaussian_int_gcd_test(
+        #[case] a: GaussianInt<i64>,
+        #[case] b: GaussianInt<i64>,
+        #[case] expected: GaussianInt<i64>,
+    ) {
+        assert_eq!(expected, a.gcd(b));
+        assert_eq!(expected, b.euclidean(a));
+    }
+
+    #[test]
+    fn gaussian_int_gcd_range_test() {
+        // The gcd divides both inputs, and its norm divides the gcd of the norms
+        for re in -8..=8 {
+            for im in -8..=8 {
+                let (a, b) = (g(re, im), g(12, 5) * g(1, 1));
+                let d = a.gcd(b);
+                assert_eq!(g(0, 0), a % d);
+                assert_eq!(g(0, 0), b % d);
+                assert_eq!(0, a.norm().euclidean(b.norm()) % d.norm());
+            }
+        }
+    }
+
+    #[rstest]
+    #[case(g(1, 1), true)]
+    #[case(g(3, 0), true)]
+    #[case(g(0, -7), true)]
+    #[case(g(2, 1), true)]
+    #[case(g(5, 0), false)]
+    #[case(g(2, 0), false)]
+    #[case(g(1, 0), false)]
+    #[case(g(3, 3), false)]
+    #[case(g(0, 0), false)]
+    fn gaussian_int_primality_test(#[case] z: GaussianInt<i64>, #[case] expected: bool) {
+        assert_eq!(expected, z.primality())
+    }
+
+    #[rstest]
+    #[case(1, 2, (1, 1))]
+    #[case(1, 5, (2, 1))]
+    #[case(1, 13, (3, 2))]
+    #[case(2, 11, (3, 1))]
+    #[case(3, 7, (2, 1))]
+    fn i64_cornacchia_test(#[case] d: i64, #[case] p: i64, #[case] expected: (i64, i64)) {
+        assert_eq!(expected, cornacchia(d, p).unwrap())
+    }
+
+    #[test]
+    fn u64_cornacchia_range_test() {
+        for p in (5..2000u64).step_by(4).filter(|p| p.primality()) {
+            let (x, y) = cornacchia(1, p).unwrap();
+            assert_eq!(p, x * x + y * y);
+        }
+        let (x, y) = cornacchia(1, 1000000009u64).unwrap();
+        assert_eq!(1000000009, x * x + y * y);
+    }
+
+    #[rstest]
+    #[case(1, 7)]
+    #[case(1, 3)]
+    #[case(5, 13)]
+    fn i64_cornacchia_no_solution_test(#[case] d: i64, #[case] p: i64) {
+        assert!(matches!(
+            cornacchia(d, p),
+            Err(NumberTheoryErr::NoSolution(_))
+        ))
+    }
+
+    #[rstest]
+    #[case(0, 5)]
+    #[case(5, 5)]
+    #[case(-1, 5)]
+    fn i64_cornacchia_domain_test(#[case] d: i64, #[case] p: i64) {
+        assert!(matches!(cornacchia(d, p), Err(NumberTheoryErr::Domain(_))))
+    }
+
+    #[rstest]
+    #[case(g(2, 0), vec![g(0, -1), g(1, 1), g(1, 1)])]
+    #[case(g(5, 0), vec![g(0, -1), g(2, 1), g(1, 2)])]
+    #[case(g(3, 4), vec![g(2, 1), g(2, 1)])]
+    #[case(g(21, 0), vec![g(3, 0), g(7, 0)])]
+    #[case(g(1, 0), vec![])]
+    #[case(g(0, -1), vec![g(0, -1)])]
+    #[case(g(0, 0), vec![g(0, 0)])]
+    fn gaussian_int_prime_factorization_test(
+        #[case] z: GaussianInt<i64>,
+        #[case] expected: Vec<GaussianInt<i64>>,
+    ) {
+        assert_eq!(expected, z.prime_factorization().unwrap())
+    }
+
+    #[test]
+    fn gaussian_int_prime_factorization_range_test() {
+        for re in -30..=30 {
+            for im in -30..=30 {
+                let z = g(re, im);
+                let factors = z.prime_factorization().unwrap();
+                let product = factors.iter().fold(g(1, 0), |acc, &f| acc * f);
+                assert_eq!(z, product);
+                if z != g(0, 0) {
+                    let start = usize::from(factors.first().is_some_and(|f| f.is_unit()));
+                    for f in &factors[start..] {
+                        assert!(f.primality());
+                        assert_eq!(*f, f.normalize());
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn gaussian_int_large_prime_factorization_test() {
+        // The norm 2 · 337 · 1361 · 2180357 is above 2^40
+        let z = g(1000003, 1000033);
+        assert!(z.norm() > 1 << 40);
+        let factors = z.prime_factorization().unwrap();
+        assert_eq!(z, factors.iter().fold(g(1, 0), |acc, &f| acc * f));
+        let primes: Vec<_> = factors.into_iter().filter(|f| !f.is_unit()).collect();
+        assert_eq!(
+            vec![2, 337, 1361, 2180357],
+            primes.iter().map(|f| f.norm()).collect::<Vec<_>>()
+        );
+        assert!(primes.iter().all(|f| f.primality()));
+    }
+
+    #[test]
+    fn gaussian_int_prime_factorization_overflow_test() {
+        assert!(matches!(
+            GaussianInt::new(i32::MAX, i32::MAX).prime_factorization(),
+            Err(NumberTheoryErr::Overflow(_))
+        ))
+    }
+
+    #[test]
+    fn gaussian_int_display_test() {
+        assert_eq!("3+4i", format!("{}", g(3, 4)));
+        assert_eq!("-1-2i", format!("{}", g(-1, -2)));
+        assert_eq!("0+0i", format!("{}", g(0, 0)));
+    }
+}
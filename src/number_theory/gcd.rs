@@ -1,12 +1,24 @@
 //! Greatest Common Divisor Trait
-use crate::general::numbers::Integer;
+use crate::general::{
+    errors::{message, NumberTheoryErr},
+    numbers::{abs, Integer},
+};
+use core::any::type_name;
+
+type Result<T> = core::result::Result<T, NumberTheoryErr>;
 
 /// Greatest Common Divisor
 ///
-/// This trait contains multiple function implementations of algorithms for finding the greatest common divisor
+/// This trait contains multiple function implementations of algorithms for finding the greatest common divisor,
+/// along with the least common multiple
+///
+/// The greatest common divisor is always non-negative, regardless of the signs of the inputs. The only
+/// exception is a result of |T::min_value()|, which is not representable and will overflow.
 pub trait Gcd<T> {
     fn gcd(self, other: T) -> T;
     fn gcd_subtraction(self, other: T) -> T;
+    fn gcd_binary(self, other: T) -> T;
+    fn lcm(self, other: T) -> Result<T>;
 }
 #[macro_export]
 macro_rules! gcd {
@@ -22,21 +34,33 @@ macro_rules! gcd {
                 let mut a = self;
                 let mut b = other;
                 while b != T::zero() {
+                    // Everything is divisible by 1 or -1, and T::min_value() % -1 overflows
+                    if b == T::one() || T::zero().checked_sub(&b) == Some(T::one()) {
+                        return T::one();
+                    }
                     let temp = b;
                     b = a % b;
                     a = temp;
                 }
 
-                // Return a
-                a
+                // The remainder takes the sign of the dividend, so a may be negative
+                abs(a)
             }
 
             /// Greatest Commmon Divisor
             ///
             /// Find the GCD of two numbers using a subtraction based method
             fn gcd_subtraction(self, other: T) -> T {
-                let mut a = self;
-                let mut b = other;
+                let mut a = abs(self);
+                let mut b = abs(other);
+
+                // Subtracting zero never makes progress, gcd(a, 0) = a
+                if a == T::zero() {
+                    return b;
+                } else if b == T::zero() {
+                    return a;
+                }
+
                 while a != b {
                     if a > b {
                         a -= b;
@@ -48,10 +72,110 @@ macro_rules! gcd {
                 // Return a
                 a
             }
+
+            /// Greatest Commmon Divisor
+            ///
+            /// Find the GCD of two numbers using Stein's binary algorithm. Factors of two are removed with
+            /// trailing zero counts and shifts, leaving only subtraction of odd numbers.
+            fn gcd_binary(self, other: T) -> T {
+                if self == T::zero() {
+                    return abs(other);
+                } else if other == T::zero() {
+                    return abs(self);
+                }
+
+                // The power of two shared by a and b, negation does not change the trailing zeros
+                let shift = (self | other).trailing_zeros() as usize;
+
+                // Strip the factors of two before taking absolute values, the arithmetic shift leaves
+                // T::min_value() at -1 instead of overflowing
+                let mut a = abs(self >> self.trailing_zeros() as usize);
+                let mut b = abs(other >> other.trailing_zeros() as usize);
+
+                // Both values stay odd at the top of the loop, so their difference is even
+                loop {
+                    b = b >> b.trailing_zeros() as usize;
+                    if a > b {
+                        core::mem::swap(&mut a, &mut b);
+                    }
+                    b -= a;
+                    if b == T::zero() {
+                        break;
+                    }
+                }
+
+                a << shift
+            }
+
+            /// Least Common Multiple
+            ///
+            /// The least non-negative common multiple, lcm(a, 0) = 0. Returns an overflow error when the
+            /// result is not representable.
+            fn lcm(self, other: T) -> Result<T> {
+                let zero = T::zero();
+                if self == zero || other == zero {
+                    return Ok(zero);
+                }
+
+                // Divide before multiplying to keep intermediate values as small as the result
+                let overflow = || {
+                    NumberTheoryErr::Overflow(message!(
+                        "Least common multiple overflows {}",
+                        type_name::<T>()
+                    ))
+                };
+                let product = (self / self.gcd(other))
+                    .checked_mul(&other)
+                    .ok_or_else(overflow)?;
+                if product < zero {
+                    zero.checked_sub(&product).ok_or_else(overflow)
+                } else {
+                    Ok(product)
+                }
+            }
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! gcd_fold {
+    ($t: ident) => {
+        /// Greatest Common Divisor of many numbers
+        ///
+        /// Fold gcd over the values, stopping early once the gcd reaches one. The gcd of no values is 0.
+        pub fn gcd_all<T, I>(values: I) -> T
+        where
+            T: $t,
+            I: IntoIterator<Item = T>,
+        {
+            let mut acc = T::zero();
+            for value in values {
+                acc = acc.gcd(value);
+                if acc == T::one() {
+                    break;
+                }
+            }
+            acc
+        }
+
+        /// Least Common Multiple of many numbers
+        ///
+        /// Fold lcm over the values, returning an overflow error as soon as the running lcm overflows.
+        /// The lcm of no values is 1.
+        pub fn lcm_all<T, I>(values: I) -> Result<T>
+        where
+            T: $t,
+            I: IntoIterator<Item = T>,
+        {
+            values
+                .into_iter()
+                .try_fold(T::one(), |acc, value| acc.lcm(value))
         }
     };
 }
+
 gcd!(Integer);
+gcd_fold!(Integer);
 
 #[cfg(test)]
 mod tests {
@@ -68,6 +192,11 @@ mod tests {
     #[rstest]
     #[case(1073, 29, 29)]
     #[case(1073, 25, 1)]
+    #[case(-4, 6, 2)]
+    #[case(4, -6, 2)]
+    #[case(-4, -6, 2)]
+    #[case(0, -6, 6)]
+    #[case(-6, 0, 6)]
     fn isize_gcd_test(#[case] a: isize, #[case] b: isize, #[case] expected: isize) {
         assert_eq!(expected, a.gcd(b))
     }
@@ -75,6 +204,9 @@ mod tests {
     #[rstest]
     #[case(1073, 29, 29)]
     #[case(1073, 25, 1)]
+    #[case(0, 25, 25)]
+    #[case(25, 0, 25)]
+    #[case(0, 0, 0)]
     fn usize_gcd_subtraction_test(#[case] a: usize, #[case] b: usize, #[case] expected: usize) {
         assert_eq!(expected, a.gcd_subtraction(b))
     }
@@ -82,7 +214,133 @@ mod tests {
     #[rstest]
     #[case(1073, 29, 29)]
     #[case(1073, 25, 1)]
+    #[case(-4, 6, 2)]
+    #[case(4, -6, 2)]
+    #[case(-4, -6, 2)]
+    #[case(0, -6, 6)]
     fn isize_gcd_subtraction_test(#[case] a: isize, #[case] b: isize, #[case] expected: isize) {
         assert_eq!(expected, a.gcd_subtraction(b))
     }
+
+    #[rstest]
+    #[case(1073, 29, 29)]
+    #[case(1073, 25, 1)]
+    #[case(48, 180, 12)]
+    #[case(0, 25, 25)]
+    #[case(25, 0, 25)]
+    #[case(0, 0, 0)]
+    #[case(1 << 40, 3 << 20, 1 << 20)]
+    fn usize_gcd_binary_test(#[case] a: usize, #[case] b: usize, #[case] expected: usize) {
+        assert_eq!(expected, a.gcd_binary(b))
+    }
+
+    #[rstest]
+    #[case(1073, 29, 29)]
+    #[case(-48, 180, 12)]
+    #[case(48, -180, 12)]
+    #[case(0, -25, 25)]
+    #[case(isize::MIN, 6, 2)]
+    #[case(-6, isize::MIN, 2)]
+    #[case(isize::MIN, 3, 1)]
+    #[case(isize::MIN, isize::MIN / 4, -(isize::MIN / 4))]
+    fn isize_gcd_binary_test(#[case] a: isize, #[case] b: isize, #[case] expected: isize) {
+        assert_eq!(expected, a.gcd_binary(b))
+    }
+
+    #[test]
+    fn gcd_algorithms_agree_test() {
+        for a in -100i32..=100 {
+            for b in -100i32..=100 {
+                assert_eq!(a.gcd(b), a.gcd_binary(b));
+                assert_eq!(a.gcd(b), a.gcd_subtraction(b));
+            }
+        }
+        assert_eq!(
+            u64::MAX.gcd(u64::MAX - 2),
+            u64::MAX.gcd_binary(u64::MAX - 2)
+        );
+        assert_eq!(u128::MAX, u128::MAX.gcd_binary(u128::MAX));
+        // Only gcd(T::min_value(), 0) = |T::min_value()| is out of range
+        for b in (-100i8..=100).filter(|b| *b != 0) {
+            assert_eq!(i8::MIN.gcd(b), i8::MIN.gcd_binary(b));
+        }
+    }
+
+    #[rstest]
+    #[case(4, 6, 12)]
+    #[case(21, 6, 42)]
+    #[case(7, 1, 7)]
+    #[case(0, 6, 0)]
+    #[case(6, 0, 0)]
+    fn usize_lcm_test(#[case] a: usize, #[case] b: usize, #[case] expected: usize) {
+        assert_eq!(expected, a.lcm(b).unwrap())
+    }
+
+    #[rstest]
+    #[case(-4, 6, 12)]
+    #[case(4, -6, 12)]
+    #[case(-4, -6, 12)]
+    #[case(0, -6, 0)]
+    fn isize_lcm_test(#[case] a: isize, #[case] b: isize, #[case] expected: isize) {
+        assert_eq!(expected, a.lcm(b).unwrap())
+    }
+
+    #[rstest]
+    #[case(u8::MAX, 2)]
+    #[case(16, 17)]
+    fn u8_lcm_overflow_test(#[case] a: u8, #[case] b: u8) {
+        assert!(matches!(a.lcm(b), Err(NumberTheoryErr::Overflow(_))))
+    }
+
+    #[test]
+    fn i8_lcm_overflow_test() {
+        assert_eq!(-(i8::MIN as i16), (i8::MIN as i16).lcm(1).unwrap());
+        assert!(matches!(i8::MIN.lcm(1), Err(NumberTheoryErr::Overflow(_))));
+        assert!(matches!(i8::MIN.lcm(-1), Err(NumberTheoryErr::Overflow(_))));
+    }
+
+    #[rstest]
+    #[case(vec![], 0)]
+    #[case(vec![12], 12)]
+    #[case(vec![12, 18, 30], 6)]
+    #[case(vec![-12, 18, -30], 6)]
+    #[case(vec![0, 0, 5], 5)]
+    #[case(vec![7, 9, 1000000], 1)]
+    fn isize_gcd_all_test(#[case] values: Vec<isize>, #[case] expected: isize) {
+        assert_eq!(expected, gcd_all(values))
+    }
+
+    #[rstest]
+    #[case(vec![], 1)]
+    #[case(vec![12], 12)]
+    #[case(vec![4, 6, 10], 60)]
+    #[case(vec![-4, 6, -10], 60)]
+    #[case(vec![4, 0, 10], 0)]
+    fn isize_lcm_all_test(#[case] values: Vec<isize>, #[case] expected: isize) {
+        assert_eq!(expected, lcm_all(values).unwrap())
+    }
+
+    #[test]
+    fn lcm_all_iterator_test() {
+        assert_eq!(2520, lcm_all(1u32..=10).unwrap());
+        assert_eq!(1, gcd_all((1u32..=10).map(|x| x * 6 + 1)));
+        assert!(matches!(
+            lcm_all(1u32..=30),
+            Err(NumberTheoryErr::Overflow(_))
+        ));
+    }
+
+    #[test]
+    fn isize_gcd_negative_range_test() {
+        for a in -100isize..=100 {
+            for b in -100isize..=100 {
+                let g = a.gcd(b);
+                assert!(g >= 0);
+                assert_eq!(g, a.abs().gcd(b.abs()));
+                if g != 0 {
+                    assert_eq!((0, 0), (a % g, b % g));
+                }
+            }
+        }
+    }
 }
@@ -0,0 +1,374 @@
+//! Integer Roots: exact k-th roots, perfect squares and perfect powers
+use crate::general::numbers::Integer;
+use core::{any::type_name, mem::size_of};
+use num::checked_pow;
+
+/// Bitmask of the squares modulo m, bit r is set when r = x^2 (mod m) for some x
+const fn square_residues(m: u32) -> u128 {
+    let mut mask = 0;
+    let mut x = 0;
+    while x < m {
+        mask |= 1 << (x * x % m);
+        x += 1;
+    }
+    mask
+}
+
+/// Only 12 of the residues mod 64 are squares, 16 of 63, 21 of 65 and 6 of 11. Together they reject
+/// all but about 1 in 100 non-squares before a root is taken.
+const SQUARE_FILTERS: [(u32, u128); 4] = [
+    (64, square_residues(64)),
+    (63, square_residues(63)),
+    (65, square_residues(65)),
+    (11, square_residues(11)),
+];
+
+/// Integer Roots Trait
+///
+/// Roots are rounded toward zero and computed with integer Newton iterations, no floating point, so
+/// they are exact for every value of T. Odd roots of negative integers are negative, even roots of
+/// negative integers do not exist and panic, like the inherent isqrt.
+///
+/// The primitive integers have an inherent isqrt with the same result, which method call syntax on a
+/// concrete type picks first. Generic code over Integer gets this one.
+///
+/// A perfect power is b^k with k >= 2 and |b| >= 2. perfect_power_decomposition returns the base and
+/// the largest such exponent, or (self, 1) when there is none, including for 0 and ±1.
+pub trait IntegerRoots<T> {
+    fn isqrt(self) -> T;
+    fn icbrt(self) -> T;
+    fn nth_root(self, k: u32) -> T;
+    fn is_perfect_square(&self) -> bool;
+    fn is_perfect_power(&self) -> bool;
+    fn perfect_power_decomposition(self) -> (T, u32);
+}
+
+#[macro_export]
+macro_rules! integer_roots {
+    ($t: ident) => {
+        impl<T> IntegerRoots<T> for T
+        where
+            T: $t,
+        {
+            /// Integer Square Root
+            ///
+            /// The largest r with r^2 <= self, by Newton's iteration r -> (r + self / r) / 2. Starting from a
+            /// power of two above the root, the iterates decrease until they reach it and never overflow.
+            /// Panics on negative input, like the inherent isqrt.
+            fn isqrt(self) -> T {
+                if self < T::zero() {
+                    panic!(
+                        "argument of integer square root of {} cannot be negative",
+                        type_name::<T>()
+                    );
+                } else if self <= T::one() {
+                    return self;
+                }
+
+                let bits = size_of::<T>() * 8 - self.leading_zeros() as usize;
+                let mut root = T::one() << bits.div_ceil(2);
+                loop {
+                    let next = (root + self / root) >> 1;
+                    if next >= root {
+                        return root;
+                    }
+                    root = next;
+                }
+            }
+
+            /// Integer Cube Root
+            fn icbrt(self) -> T {
+                self.nth_root(3)
+            }
+
+            /// Integer k-th Root
+            ///
+            /// The k-th root of |self| rounded down, by Newton's iteration r -> ((k - 1)r + n / r^(k - 1)) / k
+            /// from a power of two above the root. The step is taken as r - ⌈(r - n / r^(k - 1)) / k⌉,
+            /// which cannot overflow, and the iteration stops once n / r^(k - 1) >= r. Panics when k is zero or when an even root of a
+            /// negative number is requested.
+            fn nth_root(self, k: u32) -> T {
+                let zero = T::zero();
+                let one = T::one();
+                if k == 0 {
+                    panic!("the zeroth root of {} is undefined", type_name::<T>());
+                } else if self < zero {
+                    if k % 2 == 0 {
+                        panic!(
+                            "argument of an even root of {} cannot be negative",
+                            type_name::<T>()
+                        );
+                    }
+                    // Rounding toward zero makes odd roots symmetric. T::min_value() is not negated, its
+                    // successor has the same root unless T::min_value() is itself a k-th power.
+                    let root = zero - (zero - (self + one)).nth_root(k);
+                    let below = root - one;
+                    return match checked_pow(below, k as usize) {
+                        Some(power) if power == self => below,
+                        _ => root,
+                    };
+                } else if self <= one || k == 1 {
+                    return self;
+                }
+
+                let bits = size_of::<T>() as u32 * 8 - self.leading_zeros();
+                if k >= bits {
+                    // self < 2^bits <= 2^k
+                    return one;
+                }
+                let k_t = T::from(k).unwrap();
+                let mut root = one << bits.div_ceil(k) as usize;
+                loop {
+                    // A power that overflows T is above self, so the quotient is zero
+                    let quotient =
+                        checked_pow(root, (k - 1) as usize).map_or(zero, |power| self / power);
+                    if quotient >= root {
+                        return root;
+                    }
+                    root = root - (root - quotient + k_t - one) / k_t;
+                }
+            }
+
+            /// Perfect Square
+            ///
+            /// Check if self = r^2 for some integer r. Most non-squares are rejected by their residues
+            /// modulo 64, 63, 65 and 11 before the square root is computed.
+            fn is_perfect_square(&self) -> bool {
+                if *self < T::zero() {
+                    return false;
+                }
+                for (m, squares) in SQUARE_FILTERS {
+                    let residue = (*self % T::from(m).unwrap()).to_u32().unwrap();
+                    if squares >> residue & 1 == 0 {
+                        return false;
+                    }
+                }
+                let root = IntegerRoots::isqrt(*self);
+                root * root == *self
+            }
+
+            /// Perfect Power
+            ///
+            /// Check if self = b^k for some integers k >= 2 and |b| >= 2
+            fn is_perfect_power(&self) -> bool {
+                self.perfect_power_decomposition().1 > 1
+            }
+
+            /// Perfect Power Decomposition
+            ///
+            /// (b, k) with self = b^k for the largest k, which also makes |b| the smallest possible
+            /// base. Tries every exponent from the bit length down, negative inputs only have odd ones.
+            fn perfect_power_decomposition(self) -> (T, u32) {
+                let zero = T::zero();
+                let one = T::one();
+                if self <= one && self + one >= zero {
+                    return (self, 1);
+                }
+                let bits = size_of::<T>() as u32 * 8;
+                if self == T::min_value() {
+                    // -2^(bits - 1) has no absolute value in T, and bits - 1 is always odd
+                    return (zero - one - one, bits - 1);
+                }
+
+                let negative = self < zero;
+                let n = if negative { zero - self } else { self };
+                for k in (2..bits - n.leading_zeros()).rev() {
+                    if negative && k % 2 == 0 {
+                        continue;
+                    }
+                    let root = n.nth_root(k);
+                    if root.pow(k) == n {
+                        return (if negative { zero - root } else { root }, k);
+                    }
+                }
+                (self, 1)
+            }
+        }
+    };
+}
+
+integer_roots!(Integer);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(0, 0)]
+    #[case(1, 1)]
+    #[case(3, 1)]
+    #[case(4, 2)]
+    #[case(99, 9)]
+    #[case(100, 10)]
+    #[case(18446744073709551615, 4294967295)]
+    #[case(18446744065119617025, 4294967295)]
+    #[case(18446744065119617024, 4294967294)]
+    fn u64_isqrt_test(#[case] n: u64, #[case] expected: u64) {
+        assert_eq!(expected, IntegerRoots::isqrt(n))
+    }
+
+    #[test]
+    fn u8_isqrt_range_test() {
+        for n in 0u8..=255 {
+            let root = IntegerRoots::isqrt(n) as u16;
+            assert!(root * root <= n as u16 && (root + 1) * (root + 1) > n as u16);
+        }
+    }
+
+    #[test]
+    fn u64_isqrt_inherent_test() {
+        for n in (0u64..100000).chain(u64::MAX - 100000..=u64::MAX) {
+            assert_eq!(n.isqrt(), IntegerRoots::isqrt(n));
+        }
+    }
+
+    #[test]
+    fn i128_isqrt_max_test() {
+        // i128::MAX = 2^127 - 1 and floor(√(2^127)) = 13043817825332782212
+        assert_eq!(13043817825332782212, IntegerRoots::isqrt(i128::MAX));
+        assert_eq!(1 << 63, IntegerRoots::isqrt((1i128 << 126) + 1));
+    }
+
+    #[test]
+    #[should_panic]
+    fn i32_isqrt_negative_test() {
+        IntegerRoots::isqrt(-4i32);
+    }
+
+    #[rstest]
+    #[case(27, 3)]
+    #[case(26, 2)]
+    #[case(-27, -3)]
+    #[case(-26, -2)]
+    #[case(-1, -1)]
+    #[case(9223372036854775807, 2097151)]
+    #[case(-9223372036854775808, -2097152)]
+    fn i64_icbrt_test(#[case] n: i64, #[case] expected: i64) {
+        assert_eq!(expected, n.icbrt())
+    }
+
+    #[rstest]
+    #[case(18446744073709551615, 1, 18446744073709551615)]
+    #[case(18446744073709551615, 3, 2642245)]
+    #[case(18446744073709551615, 63, 2)]
+    #[case(18446744073709551615, 64, 1)]
+    #[case(18446744073709551615, 200, 1)]
+    #[case(12157665459056928801, 40, 3)]
+    #[case(12157665459056928800, 40, 2)]
+    #[case(0, 5, 0)]
+    fn u64_nth_root_test(#[case] n: u64, #[case] k: u32, #[case] expected: u64) {
+        assert_eq!(expected, n.nth_root(k))
+    }
+
+    #[test]
+    fn i16_nth_root_range_test() {
+        // Compare against the largest r with |r|^k <= |n| found by counting up
+        for n in i16::MIN..=i16::MAX {
+            let magnitude = (n as i32).abs();
+            for k in (1..=17).filter(|k| n >= 0 || k % 2 == 1) {
+                let mut expected = 0i32;
+                while (expected + 1).pow(k) <= magnitude {
+                    expected += 1;
+                }
+                let expected = if n < 0 { -expected } else { expected };
+                assert_eq!(expected as i16, n.nth_root(k));
+            }
+        }
+    }
+
+    #[test]
+    fn u128_nth_root_max_test() {
+        assert_eq!(u64::MAX as u128, u128::MAX.nth_root(2));
+        assert_eq!(2, u128::MAX.nth_root(127));
+        assert_eq!(1, u128::MAX.nth_root(128));
+        assert_eq!(1 << 32, (1u128 << 96).nth_root(3));
+        assert_eq!((1 << 32) - 1, ((1u128 << 96) - 1).nth_root(3));
+    }
+
+    #[test]
+    #[should_panic]
+    fn i32_nth_root_even_negative_test() {
+        (-16i32).nth_root(4);
+    }
+
+    #[test]
+    #[should_panic]
+    fn u32_nth_root_zero_test() {
+        16u32.nth_root(0);
+    }
+
+    #[rstest]
+    #[case(0, true)]
+    #[case(1, true)]
+    #[case(2, false)]
+    #[case(-4, false)]
+    #[case(144, true)]
+    #[case(145, false)]
+    #[case(9223372030926249001, true)]
+    #[case(9223372030926249000, false)]
+    fn i64_is_perfect_square_test(#[case] n: i64, #[case] expected: bool) {
+        assert_eq!(expected, n.is_perfect_square())
+    }
+
+    #[test]
+    fn is_perfect_square_filter_test() {
+        for n in 0u32..200000 {
+            let root = IntegerRoots::isqrt(n);
+            assert_eq!(root * root == n, n.is_perfect_square());
+        }
+        for n in i8::MIN..=i8::MAX {
+            assert_eq!(
+                [0, 1, 4, 9, 16, 25, 36, 49, 64, 81, 100, 121].contains(&n),
+                n.is_perfect_square()
+            );
+        }
+    }
+
+    #[rstest]
+    #[case(64, 2, 6)]
+    #[case(1000, 10, 3)]
+    #[case(36, 6, 2)]
+    #[case(-64, -4, 3)]
+    #[case(-32, -2, 5)]
+    #[case(-4, -4, 1)]
+    #[case(12, 12, 1)]
+    #[case(0, 0, 1)]
+    #[case(1, 1, 1)]
+    #[case(-1, -1, 1)]
+    #[case(4052555153018976267, 3, 39)]
+    #[case(-9223372036854775808, -2, 63)]
+    #[case(9223372036854775807, 9223372036854775807, 1)]
+    fn i64_perfect_power_decomposition_test(
+        #[case] n: i64,
+        #[case] base: i64,
+        #[case] exponent: u32,
+    ) {
+        assert_eq!((base, exponent), n.perfect_power_decomposition());
+        assert_eq!(exponent > 1, n.is_perfect_power());
+    }
+
+    #[test]
+    fn i8_perfect_power_range_test() {
+        for n in i8::MIN..=i8::MAX {
+            let (base, exponent) = n.perfect_power_decomposition();
+            assert_eq!(n as i32, (base as i32).pow(exponent));
+            let expected = (-12i32..=12)
+                .filter(|b| b.abs() >= 2)
+                .any(|b| (2..8).any(|k| b.pow(k) == n as i32));
+            assert_eq!(expected, n.is_perfect_power());
+        }
+        for n in u8::MIN..=u8::MAX {
+            let (base, exponent) = n.perfect_power_decomposition();
+            assert_eq!(n as u32, (base as u32).pow(exponent));
+            assert_eq!(
+                [
+                    4, 8, 9, 16, 25, 27, 32, 36, 49, 64, 81, 100, 121, 125, 128, 144, 169, 196,
+                    216, 225, 243
+                ]
+                .contains(&n),
+                n.is_perfect_power()
+            );
+        }
+    }
+}
@@ -0,0 +1,221 @@
+//! Linear Congruences: ax = b (mod m)
+use crate::{
+    general::{
+        errors::{message, NumberTheoryErr},
+        numbers::{abs, Integer},
+    },
+    number_theory::{
+        gcd::Gcd, modular_exponentiation::ModularExponentiation, modulus::Modulus,
+        multiplicative_inverse::MultiplicativeInverse,
+    },
+};
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
+use core::fmt::Display;
+
+type Result<T> = core::result::Result<T, NumberTheoryErr>;
+
+/// Linear Congruences
+///
+/// Solves ax = b (mod m). Solutions exist exactly when g = gcd(a, m) divides b, otherwise a NoSolution
+/// error is returned. The solutions then form a single residue class modulo |m| / g, which is g
+/// distinct residues modulo m. The Bezout coefficients behind the inverse are negative in general, so
+/// unsigned types are widened to i128, and an overflow error is returned for u128 moduli above i128::MAX.
+pub trait LinearCongruence<T> {
+    fn linear_congruence(self, b: T, m: T) -> Result<(T, T)>;
+    #[cfg(feature = "alloc")]
+    fn linear_congruence_residues(self, b: T, m: T) -> Result<Vec<T>>;
+}
+
+#[macro_export]
+macro_rules! linear_congruence {
+    ($t: ident) => {
+        impl<T: $t + Display> LinearCongruence<T> for T {
+            /// Linear Congruence
+            ///
+            /// Returns (x0, n) such that self * x = b (mod m) exactly when x = x0 (mod n), with x0 in [0, n)
+            fn linear_congruence(self, b: T, m: T) -> Result<(T, T)> {
+                if m == T::zero() {
+                    return Err(NumberTheoryErr::Domain(message!(
+                        "The modulus of {}x = {} must be non-zero",
+                        self,
+                        b
+                    )));
+                }
+
+                // Work with the least non-negative residues
+                let m = abs(m);
+                let a = self.modulus(m);
+                let c = b.modulus(m);
+                let g = a.gcd(m);
+                if c % g != T::zero() {
+                    return Err(NumberTheoryErr::NoSolution(message!(
+                        "gcd({}, {}) = {} does not divide {}",
+                        self,
+                        m,
+                        g,
+                        b
+                    )));
+                }
+
+                // Divide through by g, leaving an invertible coefficient modulo n
+                let n = m / g;
+                if n == T::one() {
+                    return Ok((T::zero(), n));
+                }
+                let x0 = inverse(a / g, n)?.mod_mul(c / g, n);
+                Ok((x0, n))
+            }
+
+            /// Linear Congruence Residues
+            ///
+            /// Every x in [0, |m|) with self * x = b (mod m), in increasing order
+            #[cfg(feature = "alloc")]
+            fn linear_congruence_residues(self, b: T, m: T) -> Result<Vec<T>> {
+                let (x0, n) = self.linear_congruence(b, m)?;
+                let m = abs(m);
+                let mut residues = vec![];
+                let mut x = x0;
+                while x < m {
+                    residues.push(x);
+                    // The last step may pass T::max_value() when m is close to it
+                    match x.checked_add(&n) {
+                        Some(next) => x = next,
+                        None => break,
+                    }
+                }
+                Ok(residues)
+            }
+        }
+    };
+}
+
+linear_congruence!(Integer);
+
+/// Inverse of a modulo n, widened to i128 for unsigned types like ModInt's inv_mod
+fn inverse<T: Integer + Display>(a: T, n: T) -> Result<T> {
+    if T::min_value() < T::zero() {
+        return a.multiplicative_inverse(n);
+    }
+    match (a.to_i128(), n.to_i128()) {
+        (Some(wide_a), Some(wide_n)) => {
+            Ok(T::from_i128(wide_a.multiplicative_inverse(wide_n)?).unwrap())
+        }
+        _ => Err(NumberTheoryErr::Overflow(message!(
+            "Inverse of {} mod {} does not fit in i128",
+            a,
+            n
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(14, 30, 100, (45, 50))]
+    #[case(3, -1, 7, (2, 7))]
+    #[case(-3, 1, 7, (2, 7))]
+    #[case(3, 1, -7, (5, 7))]
+    #[case(0, 0, 3, (0, 1))]
+    #[case(5, 3, 1, (0, 1))]
+    #[case(1073, 1, 25, (12, 25))]
+    fn isize_linear_congruence_test(
+        #[case] a: isize,
+        #[case] b: isize,
+        #[case] m: isize,
+        #[case] expected: (isize, isize),
+    ) {
+        assert_eq!(expected, a.linear_congruence(b, m).unwrap())
+    }
+
+    #[rstest]
+    #[case(14, 30, 100, vec![45, 95])]
+    #[case(0, 0, 3, vec![0, 1, 2])]
+    #[case(6, 3, 9, vec![2, 5, 8])]
+    fn isize_linear_congruence_residues_test(
+        #[case] a: isize,
+        #[case] b: isize,
+        #[case] m: isize,
+        #[case] expected: Vec<isize>,
+    ) {
+        assert_eq!(expected, a.linear_congruence_residues(b, m).unwrap())
+    }
+
+    #[rstest]
+    #[case(4, 3, 6)]
+    #[case(0, 1, 3)]
+    fn isize_linear_congruence_no_solution_test(
+        #[case] a: isize,
+        #[case] b: isize,
+        #[case] m: isize,
+    ) {
+        assert!(matches!(
+            a.linear_congruence(b, m),
+            Err(NumberTheoryErr::NoSolution(_))
+        ))
+    }
+
+    #[rstest]
+    #[case(14, 30, 100, (45, 50))]
+    #[case(3, 1, 7, (5, 7))]
+    #[case(6, 3, 9, (2, 3))]
+    fn u32_linear_congruence_test(
+        #[case] a: u32,
+        #[case] b: u32,
+        #[case] m: u32,
+        #[case] expected: (u32, u32),
+    ) {
+        assert_eq!(expected, a.linear_congruence(b, m).unwrap());
+        assert_eq!(
+            (0..m).filter(|x| (a * x) % m == b % m).collect::<Vec<_>>(),
+            a.linear_congruence_residues(b, m).unwrap()
+        );
+    }
+
+    #[test]
+    fn unsigned_linear_congruence_full_width_test() {
+        assert_eq!(
+            (6148914691236517205, u64::MAX - 1),
+            3u64.linear_congruence(1, u64::MAX - 1).unwrap()
+        );
+        assert_eq!(
+            (u64::MAX / 3 - 1, u64::MAX / 3),
+            3u64.linear_congruence(u64::MAX - 3, u64::MAX).unwrap()
+        );
+
+        // Moduli above i128::MAX cannot be widened
+        assert!(matches!(
+            3u128.linear_congruence(1, u128::MAX - 1),
+            Err(NumberTheoryErr::Overflow(_))
+        ));
+    }
+
+    #[test]
+    fn isize_linear_congruence_zero_modulus_test() {
+        assert!(matches!(
+            3isize.linear_congruence(1, 0),
+            Err(NumberTheoryErr::Domain(_))
+        ))
+    }
+
+    #[test]
+    fn isize_linear_congruence_range_test() {
+        // Compare against an exhaustive search over the residues
+        for m in (-15isize..=15).filter(|m| *m != 0) {
+            for a in -20isize..=20 {
+                for b in -20isize..=20 {
+                    let expected: Vec<isize> = (0..m.abs())
+                        .filter(|x| (a * x - b).rem_euclid(m) == 0)
+                        .collect();
+                    match a.linear_congruence_residues(b, m) {
+                        Ok(residues) => assert_eq!(expected, residues),
+                        Err(_) => assert!(expected.is_empty()),
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,391 @@
+//! Linear Diophantine Equations: ax + by = c
+use crate::{
+    general::{
+        errors::{message, NumberTheoryErr},
+        numbers::Integer,
+    },
+    number_theory::{gcd::Gcd, linear_congruence::LinearCongruence},
+};
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
+use core::fmt::Display;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+type Result<T> = core::result::Result<T, NumberTheoryErr>;
+
+/// General solution of a linear Diophantine equation
+///
+/// Every solution of ax + by = c is (x0 + step_x * t, y0 - step_y * t) for some integer t, where
+/// step_x = b / gcd(a, b) and step_y = a / gcd(a, b). The particular solution is the one with x0 in
+/// [0, |step_x|), or the only possible x when b = 0.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DiophantineSolution<T> {
+    pub x0: T,
+    pub y0: T,
+    pub step_x: T,
+    pub step_y: T,
+}
+
+impl<T: Integer> DiophantineSolution<T> {
+    /// The solution for the parameter t, or None if it overflows
+    pub fn solution(&self, t: T) -> Option<(T, T)> {
+        let x = self.x0.checked_add(&self.step_x.checked_mul(&t)?)?;
+        let y = self.y0.checked_sub(&self.step_y.checked_mul(&t)?)?;
+        Some((x, y))
+    }
+}
+
+/// Linear Diophantine Equations
+///
+/// Solves ax + by = c over the integers. Solutions exist exactly when gcd(a, b) divides c, otherwise a
+/// NoSolution error is returned. Unsigned types return an overflow error whenever the particular
+/// solution has a negative y0.
+pub trait LinearDiophantine<T> {
+    fn linear_diophantine(self, b: T, c: T) -> Result<DiophantineSolution<T>>;
+    #[cfg(feature = "alloc")]
+    fn non_negative_solutions(self, b: T, c: T) -> Result<Vec<(T, T)>>;
+    fn frobenius_number(self, b: T) -> Result<T>;
+}
+
+#[macro_export]
+macro_rules! linear_diophantine {
+    ($t: ident) => {
+        impl<T: $t + Display> LinearDiophantine<T> for T {
+            /// Linear Diophantine Equation
+            ///
+            /// Return the general solution of self * x + b * y = c
+            fn linear_diophantine(self, b: T, c: T) -> Result<DiophantineSolution<T>> {
+                let zero = T::zero();
+                let a = self;
+                if a == zero && b == zero {
+                    return if c == zero {
+                        Err(NumberTheoryErr::Domain(message!(
+                            "Every pair of integers solves 0x + 0y = 0"
+                        )))
+                    } else {
+                        Err(NumberTheoryErr::NoSolution(message!(
+                            "0x + 0y = {} has no solutions",
+                            c
+                        )))
+                    };
+                }
+
+                // Solutions exist only when the gcd divides c
+                let g = a.gcd(b);
+                if c % g != zero {
+                    return Err(NumberTheoryErr::NoSolution(message!(
+                        "gcd({}, {}) = {} does not divide {}",
+                        a,
+                        b,
+                        g,
+                        c
+                    )));
+                }
+                let (step_x, step_y) = (b / g, a / g);
+                let overflow = || {
+                    NumberTheoryErr::Overflow(message!("Overflow solving {}x + {}y = {}", a, b, c))
+                };
+
+                // With b = 0 the value of x is fixed and y is free
+                if step_x == zero {
+                    return Ok(DiophantineSolution {
+                        x0: c / a,
+                        y0: zero,
+                        step_x,
+                        step_y,
+                    });
+                }
+
+                // The values of x are exactly the solutions of ax = c (mod b), take the least non-negative one
+                let (x0, _) = a.linear_congruence(c, b)?;
+                let y0 = c
+                    .checked_sub(&a.checked_mul(&x0).ok_or_else(overflow)?)
+                    .ok_or_else(overflow)?
+                    / b;
+
+                Ok(DiophantineSolution {
+                    x0,
+                    y0,
+                    step_x,
+                    step_y,
+                })
+            }
+
+            /// Non-negative solutions
+            ///
+            /// Every solution of self * x + b * y = c with x, y >= 0 for positive self and b, in order of
+            /// increasing x. These are the ways to pay c with coins of value self and b.
+            #[cfg(feature = "alloc")]
+            fn non_negative_solutions(self, b: T, c: T) -> Result<Vec<(T, T)>> {
+                let zero = T::zero();
+                if self <= zero || b <= zero {
+                    return Err(NumberTheoryErr::Domain(message!(
+                        "Coefficients must be positive, found {} and {}",
+                        self,
+                        b
+                    )));
+                } else if c < zero {
+                    return Ok(vec![]);
+                }
+
+                // x0 is in [0, step_x) so x stays non-negative for t >= 0, y stays non-negative up to y0 / step_y.
+                // Computing y0 only overflows when it is negative, which unsigned types cannot represent.
+                let general = match self.linear_diophantine(b, c) {
+                    Err(NumberTheoryErr::Overflow(_)) => return Ok(vec![]),
+                    general => general?,
+                };
+                if general.y0 < zero {
+                    return Ok(vec![]);
+                }
+                let mut solutions = vec![];
+                let mut t = zero;
+                while t <= general.y0 / general.step_y {
+                    // Solutions are bounded by c, so they cannot overflow
+                    solutions.push(general.solution(t).unwrap());
+                    t += T::one();
+                }
+                Ok(solutions)
+            }
+
+            /// Frobenius number
+            ///
+            /// The largest amount that cannot be paid with coins of value self and b, which must be
+            /// positive and coprime: self * b - self - b. A coin of 1 pays every amount, so it returns a
+            /// Domain error for every type rather than -1.
+            fn frobenius_number(self, b: T) -> Result<T> {
+                let one = T::one();
+                if self < one || b < one || self.gcd(b) != one {
+                    return Err(NumberTheoryErr::Domain(message!(
+                        "Coin values must be positive and coprime, found {} and {}",
+                        self,
+                        b
+                    )));
+                } else if self == one || b == one {
+                    return Err(NumberTheoryErr::Domain(message!(
+                        "Every non-negative amount is representable with {} and {}",
+                        self,
+                        b
+                    )));
+                }
+
+                // (a - 1)(b - 1) - 1 = ab - a - b, which is positive once both coins are at least 2
+                (self - one)
+                    .checked_mul(&(b - one))
+                    .map(|product| product - one)
+                    .ok_or_else(|| {
+                        NumberTheoryErr::Overflow(message!(
+                            "Frobenius number of {} and {} overflows",
+                            self,
+                            b
+                        ))
+                    })
+            }
+        }
+    };
+}
+
+linear_diophantine!(Integer);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(3, 5, 7, (4, -1, 5, 3))]
+    #[case(6, 9, 21, (2, 1, 3, 2))]
+    #[case(-4, 6, 2, (1, 1, 3, -2))]
+    #[case(4, -6, 2, (2, 1, -3, 2))]
+    #[case(1073, 25, 1, (12, -515, 25, 1073))]
+    #[case(0, 5, 10, (0, 2, 1, 0))]
+    #[case(4, 0, -8, (-2, 0, 0, 1))]
+    fn isize_linear_diophantine_test(
+        #[case] a: isize,
+        #[case] b: isize,
+        #[case] c: isize,
+        #[case] expected: (isize, isize, isize, isize),
+    ) {
+        let general = a.linear_diophantine(b, c).unwrap();
+        assert_eq!(
+            expected,
+            (general.x0, general.y0, general.step_x, general.step_y)
+        );
+        for t in -10..=10 {
+            let (x, y) = general.solution(t).unwrap();
+            assert_eq!(c, a * x + b * y);
+        }
+    }
+
+    #[rstest(
+        case(6, 9, 20),
+        case(-4, 6, 1),
+        case(0, 0, 1),
+        #[should_panic]
+        case(3, 5, 7)
+    )]
+    fn isize_linear_diophantine_no_solution_test(
+        #[case] a: isize,
+        #[case] b: isize,
+        #[case] c: isize,
+    ) {
+        assert!(matches!(
+            a.linear_diophantine(b, c),
+            Err(NumberTheoryErr::NoSolution(_))
+        ))
+    }
+
+    #[test]
+    fn isize_linear_diophantine_range_test() {
+        // Every integer solution in a window appears in the general solution
+        for a in -12isize..=12 {
+            for b in -12isize..=12 {
+                for c in -30isize..=30 {
+                    match a.linear_diophantine(b, c) {
+                        Ok(general) => {
+                            let (x, y) = general.solution(7).unwrap();
+                            assert_eq!(c, a * x + b * y);
+                        }
+                        Err(NumberTheoryErr::NoSolution(_)) => {
+                            assert!((-30..=30).all(|x| (-30..=30).all(|y| a * x + b * y != c)))
+                        }
+                        Err(_) => assert_eq!((0, 0, 0), (a, b, c)),
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn i64_linear_diophantine_overflow_test() {
+        assert!(matches!(
+            i64::MAX.linear_diophantine(i64::MAX - 2, 1),
+            Err(NumberTheoryErr::Overflow(_))
+        ))
+    }
+
+    #[rstest]
+    #[case(3, 5, 22, vec![(4, 2)])]
+    #[case(3, 5, 30, vec![(0, 6), (5, 3), (10, 0)])]
+    #[case(3, 5, 7, vec![])]
+    #[case(3, 5, 0, vec![(0, 0)])]
+    #[case(3, 5, -1, vec![])]
+    #[case(6, 9, 21, vec![(2, 1)])]
+    fn isize_non_negative_solutions_test(
+        #[case] a: isize,
+        #[case] b: isize,
+        #[case] c: isize,
+        #[case] expected: Vec<(isize, isize)>,
+    ) {
+        assert_eq!(expected, a.non_negative_solutions(b, c).unwrap())
+    }
+
+    #[rstest]
+    #[case(3, 5, 22, (4, 2, 5, 3))]
+    #[case(6, 9, 21, (2, 1, 3, 2))]
+    #[case(1073, 25, 30000, (0, 1200, 25, 1073))]
+    #[case(0, 5, 10, (0, 2, 1, 0))]
+    #[case(3, u64::MAX, u64::MAX - 3, (u64::MAX / 3 - 1, 0, u64::MAX / 3, 1))]
+    fn u64_linear_diophantine_test(
+        #[case] a: u64,
+        #[case] b: u64,
+        #[case] c: u64,
+        #[case] expected: (u64, u64, u64, u64),
+    ) {
+        let general = a.linear_diophantine(b, c).unwrap();
+        assert_eq!(
+            expected,
+            (general.x0, general.y0, general.step_x, general.step_y)
+        );
+    }
+
+    #[test]
+    fn u32_linear_diophantine_negative_y_test() {
+        // The particular solution of 3x + 5y = 7 is (4, -1)
+        assert!(matches!(
+            3u32.linear_diophantine(5, 7),
+            Err(NumberTheoryErr::Overflow(_))
+        ));
+        assert!(matches!(
+            6u32.linear_diophantine(9, 20),
+            Err(NumberTheoryErr::NoSolution(_))
+        ));
+    }
+
+    #[rstest]
+    #[case(3, 5, 22, vec![(4, 2)])]
+    #[case(3, 5, 30, vec![(0, 6), (5, 3), (10, 0)])]
+    #[case(3, 5, 7, vec![])]
+    #[case(3, 5, 0, vec![(0, 0)])]
+    #[case(6, 9, 21, vec![(2, 1)])]
+    fn u32_non_negative_solutions_test(
+        #[case] a: u32,
+        #[case] b: u32,
+        #[case] c: u32,
+        #[case] expected: Vec<(u32, u32)>,
+    ) {
+        assert_eq!(expected, a.non_negative_solutions(b, c).unwrap())
+    }
+
+    #[test]
+    fn u64_non_negative_solutions_full_width_test() {
+        assert_eq!(
+            vec![(u64::MAX / 3 - 1, 0)],
+            3u64.non_negative_solutions(u64::MAX, u64::MAX - 3).unwrap()
+        );
+        // x0 = 3^-1 (mod 2^64 - 2) is far too large for 3x + (2^64 - 2)y = 1 with y >= 0
+        assert!(3u64
+            .non_negative_solutions(u64::MAX - 1, 1)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[rstest]
+    #[case(3, 5, 7)]
+    #[case(4, 9, 23)]
+    #[case(2, 3, 1)]
+    fn isize_frobenius_number_test(#[case] a: isize, #[case] b: isize, #[case] expected: isize) {
+        let frobenius = a.frobenius_number(b).unwrap();
+        assert_eq!(expected, frobenius);
+
+        // Every amount above the Frobenius number is representable with the coins
+        assert!(a.non_negative_solutions(b, frobenius).unwrap().is_empty());
+        for c in frobenius + 1..frobenius + 50 {
+            assert!(!a.non_negative_solutions(b, c).unwrap().is_empty());
+        }
+    }
+
+    #[rstest]
+    #[case(4, 6)]
+    #[case(0, 5)]
+    #[case(-3, 5)]
+    #[case(1, 9)]
+    #[case(9, 1)]
+    fn isize_frobenius_number_domain_test(#[case] a: isize, #[case] b: isize) {
+        assert!(matches!(
+            a.frobenius_number(b),
+            Err(NumberTheoryErr::Domain(_))
+        ))
+    }
+
+    #[test]
+    fn u32_frobenius_number_test() {
+        assert_eq!(7, 3u32.frobenius_number(5).unwrap());
+        for (a, b) in [(1u32, 5), (5, 1), (4, 6), (0, 5)] {
+            assert!(matches!(
+                a.frobenius_number(b),
+                Err(NumberTheoryErr::Domain(_))
+            ));
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn diophantine_solution_serde_test() {
+        let solution = 3i64.linear_diophantine(5, 7).unwrap();
+        let json = serde_json::to_string(&solution).unwrap();
+        assert_eq!(r#"{"x0":4,"y0":-1,"step_x":5,"step_y":3}"#, json);
+        assert_eq!(solution, serde_json::from_str(&json).unwrap());
+    }
+}
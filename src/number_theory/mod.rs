@@ -1,10 +1,46 @@
+#[cfg(feature = "alloc")]
+pub mod batch_gcd;
+#[cfg(feature = "std")]
+pub mod certificate;
+pub mod const_fns;
+#[cfg(feature = "alloc")]
 pub mod continued_fraction;
+#[cfg(feature = "std")]
+pub mod ecm;
 pub mod euclidean_algs;
 pub mod euler_totient;
 pub mod factorial;
+#[cfg(feature = "std")]
+pub mod factorization;
+#[cfg(feature = "std")]
+pub mod factorization_cache;
+pub mod fermat_factoring;
+pub mod gaussian_int;
 pub mod gcd;
+pub mod integer_roots;
+pub mod linear_congruence;
+pub mod linear_diophantine;
+pub mod modular_exponentiation;
+pub mod modular_int;
 pub mod modulus;
+#[cfg(feature = "alloc")]
 pub mod multiples;
 pub mod multiplicative_inverse;
+#[cfg(feature = "rayon")]
+pub mod parallel;
 pub mod parity;
 pub mod primality;
+#[cfg(feature = "alloc")]
+pub mod prime_counting;
+pub mod pseudoprime;
+#[cfg(feature = "alloc")]
+pub mod pythagorean_triples;
+pub mod quadratic_residue;
+#[cfg(feature = "std")]
+pub mod quadratic_sieve;
+#[cfg(feature = "std")]
+pub mod random;
+#[cfg(feature = "alloc")]
+pub mod smooth_factoring;
+#[cfg(feature = "alloc")]
+pub mod sum_of_squares;
@@ -0,0 +1,175 @@
+//! Modular Exponentiation: a^e (mod m)
+use crate::{
+    general::{
+        errors::{message, NumberTheoryErr},
+        numbers::{abs, Integer},
+    },
+    number_theory::modulus::Modulus,
+};
+use core::{any::type_name, mem::size_of};
+
+type Result<T> = core::result::Result<T, NumberTheoryErr>;
+
+/// Modular Exponentiation Trait
+///
+/// Products and powers reduced modulo m without overflowing, for any modulus up to T::max_value().
+/// Results are least non-negative residues in [0, |m|).
+pub trait ModularExponentiation<T> {
+    fn mod_mul(self, b: T, m: T) -> T;
+    fn mod_pow(self, exponent: T, m: T) -> Result<T>;
+}
+
+#[macro_export]
+macro_rules! modular_exponentiation {
+    ($t: ident) => {
+        impl<T> ModularExponentiation<T> for T
+        where
+            T: $t,
+        {
+            /// Modular Multiplication
+            ///
+            /// self * b (mod m). Multiplies directly when the product fits in T, widens to u128 when
+            /// everything fits in 64 bits, and otherwise falls back to double-and-add so intermediate
+            /// values never exceed m. Panics if m is zero, like the remainder operator.
+            fn mod_mul(self, b: T, m: T) -> T {
+                let m = abs(m);
+                let a = self.modulus(m);
+                let b = b.modulus(m);
+                if let Some(product) = a.checked_mul(&b) {
+                    return product % m;
+                }
+                if let (Some(a), Some(b), Some(m)) = (a.to_u64(), b.to_u64(), m.to_u64()) {
+                    return T::from_u128(a as u128 * b as u128 % m as u128).unwrap();
+                }
+
+                // x + y (mod m) for x, y in [0, m), without computing x + y
+                let add = |x: T, y: T| if x >= m - y { x - (m - y) } else { x + y };
+                let bits = size_of::<T>() * 8 - b.leading_zeros() as usize;
+                let mut result = T::zero();
+                for i in (0..bits).rev() {
+                    result = add(result, result);
+                    if (b >> i) & T::one() == T::one() {
+                        result = add(result, a);
+                    }
+                }
+                result
+            }
+
+            /// Modular Exponentiation
+            ///
+            /// self^exponent (mod m) by square-and-multiply. The exponent must be non-negative and the
+            /// modulus non-zero.
+            fn mod_pow(self, exponent: T, m: T) -> Result<T> {
+                if m == T::zero() {
+                    return Err(NumberTheoryErr::Domain(message!(
+                        "Modular exponentiation of {} requires a non-zero modulus",
+                        type_name::<T>()
+                    )));
+                } else if exponent < T::zero() {
+                    return Err(NumberTheoryErr::Domain(message!(
+                        "Modular exponentiation of {} requires a non-negative exponent",
+                        type_name::<T>()
+                    )));
+                }
+
+                let mut result = T::one().modulus(m);
+                let mut base = self.modulus(m);
+                let mut exponent = exponent;
+                while exponent > T::zero() {
+                    if exponent & T::one() == T::one() {
+                        result = result.mod_mul(base, m);
+                    }
+                    base = base.mod_mul(base, m);
+                    exponent = exponent >> 1;
+                }
+                Ok(result)
+            }
+        }
+    };
+}
+
+modular_exponentiation!(Integer);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(3, 4, 5, 2)]
+    #[case(-3, 4, 5, 3)]
+    #[case(3, -4, 5, 3)]
+    #[case(7, 8, -5, 1)]
+    #[case(0, 9, 7, 0)]
+    #[case(9, 9, 1, 0)]
+    fn isize_mod_mul_test(
+        #[case] a: isize,
+        #[case] b: isize,
+        #[case] m: isize,
+        #[case] expected: isize,
+    ) {
+        assert_eq!(expected, a.mod_mul(b, m))
+    }
+
+    #[test]
+    fn u64_mod_mul_widening_test() {
+        let m = u64::MAX - 58;
+        let (a, b) = (u64::MAX - 100, u64::MAX - 200);
+        let expected = (a as u128 * b as u128 % m as u128) as u64;
+        assert_eq!(expected, a.mod_mul(b, m));
+    }
+
+    #[test]
+    fn u128_mod_mul_double_and_add_test() {
+        // (m - 1)^2 = 1 (mod m), (m - 2)(m - 3) = 6 (mod m) and u128::MAX = 158 (mod m)
+        let m = u128::MAX - 158;
+        assert_eq!(1, (m - 1).mod_mul(m - 1, m));
+        assert_eq!(6, (m - 2).mod_mul(m - 3, m));
+        assert_eq!(158 * 158, u128::MAX.mod_mul(u128::MAX, m));
+    }
+
+    #[rstest]
+    #[case(2, 10, 1000, 24)]
+    #[case(3, 0, 7, 1)]
+    #[case(3, 0, 1, 0)]
+    #[case(-2, 3, 7, 6)]
+    #[case(5, 117, 19, 1)]
+    #[case(4, 13, 497, 445)]
+    fn isize_mod_pow_test(
+        #[case] a: isize,
+        #[case] e: isize,
+        #[case] m: isize,
+        #[case] expected: isize,
+    ) {
+        assert_eq!(expected, a.mod_pow(e, m).unwrap())
+    }
+
+    #[test]
+    fn u128_mod_pow_fermat_test() {
+        // 2^127 - 1 is prime, so a^(p - 1) = 1 for every a it does not divide
+        let p = (1u128 << 127) - 1;
+        for a in [2u128, 3, 10, u64::MAX as u128, p - 1] {
+            assert_eq!(1, a.mod_pow(p - 1, p).unwrap());
+        }
+    }
+
+    #[test]
+    fn i8_mod_pow_range_test() {
+        for m in (-20i8..=20).filter(|m| *m != 0) {
+            for a in -20i8..=20 {
+                let mut expected = 1i64.rem_euclid(m.abs() as i64);
+                for e in 0i8..=10 {
+                    assert_eq!(expected as i8, a.mod_pow(e, m).unwrap());
+                    expected = (expected * a as i64).rem_euclid(m.abs() as i64);
+                }
+            }
+        }
+    }
+
+    #[rstest]
+    #[case(2, 3, 0)]
+    #[case(2, -1, 7)]
+    fn isize_mod_pow_domain_test(#[case] a: isize, #[case] e: isize, #[case] m: isize) {
+        assert!(matches!(a.mod_pow(e, m), Err(NumberTheoryErr::Domain(_))))
+    }
+}
@@ -0,0 +1,470 @@
+//! Modular Integers: elements of ℤ/nℤ
+use crate::{
+    general::{errors::NumberTheoryErr, numbers::Integer},
+    number_theory::{gcd::Gcd, modulus::Modulus, multiplicative_inverse::MultiplicativeInverse},
+};
+use core::{
+    fmt,
+    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+};
+
+type Result<T> = core::result::Result<T, NumberTheoryErr>;
+
+/// Integer modulo M
+///
+/// An element of ℤ/Mℤ with the modulus fixed at compile time, stored as its least non-negative residue.
+/// Arithmetic operators reduce modulo M after every operation, so they never overflow.
+///
+/// Division multiplies by the inverse and panics when the divisor is not a unit, the same way integer
+/// division panics on zero. Use inv() to handle non-invertible elements as an error instead.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ModInt<const M: u64> {
+    value: u64,
+}
+
+/// ℤ/nℤ, an alias for ModInt
+pub type Zn<const M: u64> = ModInt<M>;
+
+/// Integer modulo a runtime modulus
+///
+/// An element of ℤ/mℤ where the modulus is only known at runtime. Operations between elements of
+/// different moduli panic.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct DynModInt {
+    value: u64,
+    modulus: u64,
+}
+
+/// Reduce any integer to its least non-negative residue modulo m
+fn reduce<T: Integer>(n: T, m: u64) -> u64 {
+    match T::from_u64(m) {
+        Some(modulus) => n.modulus(modulus).to_u64().unwrap(),
+        // The modulus is larger than T::max_value(), so every T fits in an i128 alongside it
+        None => n.to_i128().unwrap().rem_euclid(m as i128) as u64,
+    }
+}
+
+fn add_mod(a: u64, b: u64, m: u64) -> u64 {
+    ((a as u128 + b as u128) % m as u128) as u64
+}
+
+fn sub_mod(a: u64, b: u64, m: u64) -> u64 {
+    ((a as u128 + m as u128 - b as u128) % m as u128) as u64
+}
+
+fn mul_mod(a: u64, b: u64, m: u64) -> u64 {
+    ((a as u128 * b as u128) % m as u128) as u64
+}
+
+/// Square and multiply exponentiation
+fn pow_mod(base: u64, mut exp: u64, m: u64) -> u64 {
+    let mut base = base % m;
+    let mut acc = 1 % m;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            acc = mul_mod(acc, base, m);
+        }
+        base = mul_mod(base, base, m);
+        exp >>= 1;
+    }
+    acc
+}
+
+/// Modular inverse through the MultiplicativeInverse trait, widened so the Bezout coefficients fit
+fn inv_mod(a: u64, m: u64) -> Result<u64> {
+    (a as i128)
+        .multiplicative_inverse(m as i128)
+        .map(|inv| inv as u64)
+}
+
+impl<const M: u64> ModInt<M> {
+    const POSITIVE_MODULUS: () = assert!(M > 0, "The modulus of a ModInt must be positive");
+
+    /// Create the residue class of n modulo M
+    pub fn new<T: Integer>(n: T) -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::POSITIVE_MODULUS;
+        Self {
+            value: reduce(n, M),
+        }
+    }
+
+    /// The modulus M
+    pub const fn modulus() -> u64 {
+        M
+    }
+
+    /// The least non-negative residue
+    pub fn value(self) -> u64 {
+        self.value
+    }
+
+    /// Exponentiation by squaring
+    pub fn pow(self, exp: u64) -> Self {
+        Self {
+            value: pow_mod(self.value, exp, M),
+        }
+    }
+
+    /// Multiplicative inverse
+    ///
+    /// Returns an InverseDNE error when gcd(value, M) is not 1
+    pub fn inv(self) -> Result<Self> {
+        inv_mod(self.value, M).map(|value| Self { value })
+    }
+
+    /// Check if the element is invertible, i.e. coprime to M
+    pub fn is_unit(self) -> bool {
+        self.value.gcd(M) == 1
+    }
+}
+
+impl DynModInt {
+    /// Create the residue class of n modulo m
+    pub fn new<T: Integer>(n: T, modulus: u64) -> Self {
+        assert!(modulus > 0, "The modulus of a DynModInt must be positive");
+        Self {
+            value: reduce(n, modulus),
+            modulus,
+        }
+    }
+
+    /// The modulus m
+    pub fn modulus(self) -> u64 {
+        self.modulus
+    }
+
+    /// The least non-negative residue
+    pub fn value(self) -> u64 {
+        self.value
+    }
+
+    /// Exponentiation by squaring
+    pub fn pow(self, exp: u64) -> Self {
+        Self {
+            value: pow_mod(self.value, exp, self.modulus),
+            ..self
+        }
+    }
+
+    /// Multiplicative inverse
+    ///
+    /// Returns an InverseDNE error when gcd(value, m) is not 1
+    pub fn inv(self) -> Result<Self> {
+        inv_mod(self.value, self.modulus).map(|value| Self { value, ..self })
+    }
+
+    /// Check if the element is invertible, i.e. coprime to m
+    pub fn is_unit(self) -> bool {
+        self.value.gcd(self.modulus) == 1
+    }
+
+    /// Both operands must share a modulus
+    fn check_modulus(self, other: Self) -> u64 {
+        assert_eq!(
+            self.modulus, other.modulus,
+            "DynModInt operands have different moduli"
+        );
+        self.modulus
+    }
+}
+
+/// The zero class, through new so that ModInt::<0>::default() fails to compile as well
+impl<const M: u64> Default for ModInt<M> {
+    fn default() -> Self {
+        Self::new(0u8)
+    }
+}
+
+impl<const M: u64> From<ModInt<M>> for DynModInt {
+    fn from(n: ModInt<M>) -> Self {
+        Self {
+            value: n.value,
+            modulus: M,
+        }
+    }
+}
+
+impl<const M: u64> fmt::Display for ModInt<M> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} (mod {})", self.value, M)
+    }
+}
+
+impl fmt::Display for DynModInt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} (mod {})", self.value, self.modulus)
+    }
+}
+
+impl<const M: u64> Add for ModInt<M> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        Self {
+            value: add_mod(self.value, rhs.value, M),
+        }
+    }
+}
+
+impl<const M: u64> Sub for ModInt<M> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        Self {
+            value: sub_mod(self.value, rhs.value, M),
+        }
+    }
+}
+
+impl<const M: u64> Mul for ModInt<M> {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        Self {
+            value: mul_mod(self.value, rhs.value, M),
+        }
+    }
+}
+
+impl<const M: u64> Div for ModInt<M> {
+    type Output = Self;
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Self {
+        self * rhs.inv().expect("Division by a non-invertible element")
+    }
+}
+
+impl<const M: u64> Neg for ModInt<M> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self {
+            value: sub_mod(0, self.value, M),
+        }
+    }
+}
+
+impl Add for DynModInt {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        let m = self.check_modulus(rhs);
+        Self {
+            value: add_mod(self.value, rhs.value, m),
+            modulus: m,
+        }
+    }
+}
+
+impl Sub for DynModInt {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        let m = self.check_modulus(rhs);
+        Self {
+            value: sub_mod(self.value, rhs.value, m),
+            modulus: m,
+        }
+    }
+}
+
+impl Mul for DynModInt {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        let m = self.check_modulus(rhs);
+        Self {
+            value: mul_mod(self.value, rhs.value, m),
+            modulus: m,
+        }
+    }
+}
+
+impl Div for DynModInt {
+    type Output = Self;
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Self {
+        self.check_modulus(rhs);
+        self * rhs.inv().expect("Division by a non-invertible element")
+    }
+}
+
+impl Neg for DynModInt {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self {
+            value: sub_mod(0, self.value, self.modulus),
+            ..self
+        }
+    }
+}
+
+/// Assignment operators defer to the binary operators
+macro_rules! modular_int_assign_ops {
+    ($($trait: ident, $method: ident, $op: tt);*) => {
+        $(
+            impl<const M: u64> $trait for ModInt<M> {
+                fn $method(&mut self, rhs: Self) {
+                    *self = *self $op rhs;
+                }
+            }
+
+            impl $trait for DynModInt {
+                fn $method(&mut self, rhs: Self) {
+                    *self = *self $op rhs;
+                }
+            }
+        )*
+    };
+}
+
+modular_int_assign_ops!(
+    AddAssign, add_assign, +;
+    SubAssign, sub_assign, -;
+    MulAssign, mul_assign, *;
+    DivAssign, div_assign, /
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(0, 0)]
+    #[case(12, 5)]
+    #[case(-1, 6)]
+    #[case(-15, 6)]
+    #[case(i64::MIN, 6)]
+    fn i64_mod_int_new_test(#[case] a: i64, #[case] expected: u64) {
+        assert_eq!(expected, ModInt::<7>::new(a).value())
+    }
+
+    #[test]
+    fn narrow_type_mod_int_new_test() {
+        // Moduli larger than the input type
+        assert_eq!(999, ModInt::<1000>::new(-1i8).value());
+        assert_eq!(255, ModInt::<1000>::new(255u8).value());
+        assert_eq!(u64::MAX - 1, ModInt::<{ u64::MAX }>::new(-1i64).value());
+    }
+
+    #[test]
+    fn mod_int_default_test() {
+        assert_eq!(ModInt::<7>::new(0u8), ModInt::<7>::default());
+        assert_eq!(0, ModInt::<{ u64::MAX }>::default().value());
+    }
+
+    #[rstest]
+    #[case(3, 5, 1)]
+    #[case(6, 6, 5)]
+    #[case(0, 0, 0)]
+    fn mod_int_add_test(#[case] a: u64, #[case] b: u64, #[case] expected: u64) {
+        assert_eq!(
+            ModInt::<7>::new(expected),
+            ModInt::<7>::new(a) + ModInt::<7>::new(b)
+        )
+    }
+
+    #[rstest]
+    #[case(3, 5, 5)]
+    #[case(5, 3, 2)]
+    #[case(0, 1, 6)]
+    fn mod_int_sub_test(#[case] a: u64, #[case] b: u64, #[case] expected: u64) {
+        assert_eq!(
+            ModInt::<7>::new(expected),
+            ModInt::<7>::new(a) - ModInt::<7>::new(b)
+        )
+    }
+
+    #[rstest]
+    #[case(3, 5, 1)]
+    #[case(6, 6, 1)]
+    #[case(4, 0, 0)]
+    fn mod_int_mul_test(#[case] a: u64, #[case] b: u64, #[case] expected: u64) {
+        assert_eq!(
+            ModInt::<7>::new(expected),
+            ModInt::<7>::new(a) * ModInt::<7>::new(b)
+        )
+    }
+
+    #[test]
+    fn mod_int_large_modulus_test() {
+        const P: u64 = 18446744073709551557; // Largest prime below 2^64
+        let a = ModInt::<P>::new(P - 1);
+        assert_eq!(ModInt::new(1), a * a);
+        assert_eq!(ModInt::new(P - 2), a + a);
+        assert_eq!(ModInt::new(1), -a);
+        assert_eq!(a, a.inv().unwrap());
+    }
+
+    #[test]
+    fn mod_int_assign_test() {
+        let mut a = ModInt::<26>::new(9);
+        a += ModInt::new(20);
+        assert_eq!(3, a.value());
+        a -= ModInt::new(5);
+        assert_eq!(24, a.value());
+        a *= ModInt::new(3);
+        assert_eq!(20, a.value());
+        a /= ModInt::new(3);
+        assert_eq!(24, a.value());
+    }
+
+    #[rstest(
+        case(9, 3),
+        case(25, 25),
+        case(1, 1),
+        #[should_panic]
+        case(13, 0)
+    )]
+    fn mod_int_inv_test(#[case] a: u64, #[case] expected: u64) {
+        let inv = ModInt::<26>::new(a).inv().unwrap();
+        assert_eq!(expected, inv.value());
+        assert_eq!(ModInt::new(1), ModInt::<26>::new(a) * inv);
+    }
+
+    #[test]
+    #[should_panic]
+    fn mod_int_div_non_unit_test() {
+        let _ = ModInt::<26>::new(1) / ModInt::new(2);
+    }
+
+    #[rstest]
+    #[case(2, 0, 1)]
+    #[case(2, 10, 1024 % 1000)]
+    #[case(3, 1000, 1)]
+    fn mod_int_pow_test(#[case] a: u64, #[case] exp: u64, #[case] expected: u64) {
+        assert_eq!(expected, ModInt::<1000>::new(a).pow(exp).value())
+    }
+
+    #[test]
+    fn mod_int_fermat_test() {
+        // a^(p - 1) = 1 (mod p) for every unit a
+        for a in 1..101u64 {
+            let a = ModInt::<101>::new(a);
+            assert!(a.is_unit());
+            assert_eq!(ModInt::new(1), a.pow(100));
+        }
+    }
+
+    #[test]
+    fn mod_int_display_test() {
+        assert_eq!("5 (mod 7)", format!("{}", Zn::<7>::new(-2)));
+        assert_eq!("5 (mod 7)", format!("{}", DynModInt::new(-2, 7)));
+    }
+
+    #[test]
+    fn dyn_mod_int_test() {
+        let a = DynModInt::new(9, 26);
+        let b = DynModInt::from(ModInt::<26>::new(21));
+        assert_eq!(DynModInt::new(4, 26), a + b);
+        assert_eq!(DynModInt::new(14, 26), a - b);
+        assert_eq!(DynModInt::new(7, 26), a * b);
+        assert_eq!(DynModInt::new(17, 26), -a);
+        assert_eq!(DynModInt::new(3, 26), a.inv().unwrap());
+        assert_eq!(a, (a / b) * b);
+        assert_eq!(DynModInt::new(1, 26), a.pow(3));
+        assert!(DynModInt::new(4, 26).inv().is_err());
+        assert!(!DynModInt::new(4, 26).is_unit());
+    }
+
+    #[test]
+    #[should_panic]
+    fn dyn_mod_int_mismatched_moduli_test() {
+        let _ = DynModInt::new(1, 7) + DynModInt::new(1, 8);
+    }
+}
@@ -6,10 +6,14 @@ use crate::general::numbers::Integer;
 /// The modulus operator (%) is the remainder operation but not a congruency modulus
 /// See: https://en.wikipedia.org/wiki/Modular_arithmetic for more information
 ///
-/// Two functions are implemented, one to find a possible congruency given the modulus, another to check if a and b are congruent given the modulus
+/// Two functions are implemented, one to find the least non-negative residue given the modulus, another to check if a and b are congruent given the modulus
+///
+/// The residue is the Euclidean remainder: for any sign of a and m, a.modulus(m) lies in [0, |m|)
 pub trait Modulus<T> {
-    fn modulus(self, b: T) -> T;
-    // fn is_congruent_modulus(self, b: T) -> bool;
+    fn modulus(self, m: T) -> T;
+    // Integers are Copy, so self is taken by value like modulus
+    #[allow(clippy::wrong_self_convention)]
+    fn is_congruent_modulus(self, b: T, m: T) -> bool;
 }
 
 #[macro_export]
@@ -19,11 +23,27 @@ macro_rules! modulus {
         where
             T: $t,
         {
-            fn modulus(self, b: T) -> T {
-                (self % b) + b
+            /// Least non-negative residue
+            ///
+            /// Returns r in [0, |m|) such that self = qm + r for some integer q
+            fn modulus(self, m: T) -> T {
+                // The remainder takes the sign of the dividend, shift negative remainders up by |m|
+                let r = self % m;
+                if r >= T::zero() {
+                    r
+                } else if m < T::zero() {
+                    r - m
+                } else {
+                    r + m
+                }
             }
 
-            // fn is_congruent_modulus(self, b: T) -> bool {}
+            /// Congruence
+            ///
+            /// Check if self and b are congruent modulo m, i.e. if m divides self - b
+            fn is_congruent_modulus(self, b: T, m: T) -> bool {
+                self.modulus(m) == b.modulus(m)
+            }
         }
     };
 }
@@ -35,18 +55,64 @@ mod tests {
     use rstest::rstest;
 
     #[rstest]
-    #[case(1, 1, 1)]
-    #[case(38, 12, 14)]
-    #[case(2, 5, 7)]
+    #[case(1, 1, 0)]
+    #[case(38, 12, 2)]
+    #[case(2, 5, 2)]
+    #[case(0, 5, 0)]
     fn usize_modulus_test(#[case] a: usize, #[case] b: usize, #[case] expected: usize) {
         assert_eq!(expected, a.modulus(b))
     }
 
     #[rstest]
-    #[case(1, 1, 1)]
-    #[case(38, 12, 14)]
-    #[case(2, 5, 7)]
+    #[case(1, 1, 0)]
+    #[case(38, 12, 2)]
+    #[case(2, 5, 2)]
+    #[case(-1, 5, 4)]
+    #[case(-38, 12, 10)]
+    #[case(-36, 12, 0)]
+    #[case(38, -12, 2)]
+    #[case(-38, -12, 10)]
     fn isize_modulus_test(#[case] a: isize, #[case] b: isize, #[case] expected: isize) {
         assert_eq!(expected, a.modulus(b))
     }
+
+    #[rstest]
+    #[case(38, 14, 12, true)]
+    #[case(38, 15, 12, false)]
+    #[case(2, 2, 5, true)]
+    fn usize_is_congruent_modulus_test(
+        #[case] a: usize,
+        #[case] b: usize,
+        #[case] m: usize,
+        #[case] expected: bool,
+    ) {
+        assert_eq!(expected, a.is_congruent_modulus(b, m))
+    }
+
+    #[rstest]
+    #[case(38, 14, 12, true)]
+    #[case(38, 15, 12, false)]
+    #[case(-1, 4, 5, true)]
+    #[case(-1, -6, 5, true)]
+    #[case(-1, 1, 5, false)]
+    #[case(-38, 10, -12, true)]
+    fn isize_is_congruent_modulus_test(
+        #[case] a: isize,
+        #[case] b: isize,
+        #[case] m: isize,
+        #[case] expected: bool,
+    ) {
+        assert_eq!(expected, a.is_congruent_modulus(b, m))
+    }
+
+    #[test]
+    fn isize_modulus_negative_range_test() {
+        for m in (-20isize..=20).filter(|m| *m != 0) {
+            for a in -200isize..=200 {
+                let r = a.modulus(m);
+                assert!(0 <= r && r < m.abs());
+                assert_eq!(0, (a - r) % m);
+            }
+        }
+    }
 }
@@ -1,5 +1,134 @@
 //! Multiples Functions
-use crate::general::numbers::Integer;
+use crate::{
+    general::{
+        errors::{message, NumberTheoryErr},
+        numbers::{abs, Integer},
+    },
+    number_theory::gcd::Gcd,
+};
+use alloc::{collections::BinaryHeap, vec, vec::Vec};
+use core::{
+    any::type_name,
+    cmp::Reverse,
+    ops::{Bound, RangeBounds},
+};
+use num::checked_pow;
+
+type Result<T> = core::result::Result<T, NumberTheoryErr>;
+
+/// Multiples of a single factor
+///
+/// Lazily yields factor, 2 * factor, 3 * factor, ... until the next multiple would overflow
+pub struct MultiplesOf<T> {
+    factor: T,
+    next: Option<T>,
+}
+
+/// Multiples of several factors
+///
+/// Lazily merges the arithmetic progressions of every factor, yielding each multiple once and in
+/// increasing order. Each progression ends when its next multiple would overflow.
+pub struct Multiples<T> {
+    factors: Vec<T>,
+    heap: BinaryHeap<Reverse<(T, usize)>>,
+}
+
+#[macro_export]
+macro_rules! multiples_iterators {
+    ($t: ident) => {
+        impl<T> MultiplesOf<T>
+        where
+            T: $t,
+        {
+            /// Multiples of |factor|, no multiples are produced for a zero factor
+            pub fn new(factor: T) -> Self {
+                let factor = abs(factor);
+                let next = if factor == T::zero() {
+                    None
+                } else {
+                    Some(factor)
+                };
+                MultiplesOf { factor, next }
+            }
+        }
+
+        impl<T> Iterator for MultiplesOf<T>
+        where
+            T: $t,
+        {
+            type Item = T;
+
+            fn next(&mut self) -> Option<T> {
+                let current = self.next?;
+                self.next = current.checked_add(&self.factor);
+                Some(current)
+            }
+        }
+
+        impl<T> Multiples<T>
+        where
+            T: $t,
+        {
+            /// Multiples of the absolute values of the factors, zero factors are ignored
+            pub fn new(factors: Vec<T>) -> Self {
+                Self::starting_at(factors, T::one())
+            }
+
+            /// Multiples of the factors that are greater than or equal to start, skipping the smaller
+            /// multiples without generating them
+            pub fn starting_at(factors: Vec<T>, start: T) -> Self {
+                let factors: Vec<T> = factors
+                    .into_iter()
+                    .map(abs)
+                    .filter(|factor| *factor != T::zero())
+                    .collect();
+                let mut heap = BinaryHeap::with_capacity(factors.len());
+                for (i, factor) in factors.iter().enumerate() {
+                    // The first positive multiple at or above start, ceil(start / factor) * factor
+                    let first = if start <= *factor {
+                        Some(*factor)
+                    } else {
+                        let quotient = start / *factor;
+                        if start % *factor == T::zero() {
+                            quotient.checked_mul(factor)
+                        } else {
+                            (quotient + T::one()).checked_mul(factor)
+                        }
+                    };
+                    if let Some(first) = first {
+                        heap.push(Reverse((first, i)));
+                    }
+                }
+                Multiples { factors, heap }
+            }
+        }
+
+        impl<T> Iterator for Multiples<T>
+        where
+            T: $t,
+        {
+            type Item = T;
+
+            fn next(&mut self) -> Option<T> {
+                let Reverse((current, _)) = *self.heap.peek()?;
+
+                // Advance every progression sitting at the current value, which removes duplicates
+                while let Some(Reverse((value, i))) = self.heap.peek().copied() {
+                    if value != current {
+                        break;
+                    }
+                    self.heap.pop();
+                    if let Some(next) = value.checked_add(&self.factors[i]) {
+                        self.heap.push(Reverse((next, i)));
+                    }
+                }
+                Some(current)
+            }
+        }
+    };
+}
+
+multiples_iterators!(Integer);
 
 #[macro_export]
 macro_rules! multiples {
@@ -8,25 +137,14 @@ macro_rules! multiples {
         ///
         /// Vector of all unique multiples of a number that fit within the range
         /// [factor, maximum representable number for the type]
+        ///
+        /// The vector holds T::max_value() / factor values, use MultiplesOf to iterate over them lazily
         #[allow(dead_code)]
         pub fn multiples_of<T>(factor: T) -> Vec<T>
         where
             T: $t,
         {
-            let mut multiples = vec![];
-            let mut i = T::one();
-            'find: loop {
-                let current = factor.checked_mul(&i);
-                if current.is_some() {
-                    multiples.push(current.unwrap());
-                } else {
-                    break 'find;
-                }
-                i += T::one();
-            }
-            multiples.sort();
-            multiples.dedup();
-            multiples
+            MultiplesOf::new(factor).collect()
         }
 
         /// Multiples
@@ -37,39 +155,199 @@ macro_rules! multiples {
         where
             T: $t,
         {
-            let mut multiples = vec![];
-            for factor in factors {
-                let mut i = T::one();
-                'find: while i <= (max / factor) {
-                    let current = factor.checked_mul(&i);
-                    if current.is_some() {
-                        if current.unwrap() < max {
-                            multiples.push(current.unwrap());
-                        } else {
-                            break 'find;
-                        }
+            multiples_in(factors, ..max).collect()
+        }
+
+        /// Multiples in a range
+        ///
+        /// Lazily iterate over the unique positive multiples of the input factors that lie in the range,
+        /// in increasing order
+        #[allow(dead_code)]
+        pub fn multiples_in<T, R>(factors: Vec<T>, range: R) -> impl Iterator<Item = T>
+        where
+            T: $t,
+            R: RangeBounds<T>,
+        {
+            let start = match range.start_bound() {
+                Bound::Included(start) => *start,
+                Bound::Excluded(start) => start.saturating_add(T::one()),
+                Bound::Unbounded => T::one(),
+            };
+            let end = range.end_bound().cloned();
+            Multiples::starting_at(factors, start).take_while(move |multiple| match end {
+                Bound::Included(end) => *multiple <= end,
+                Bound::Excluded(end) => *multiple < end,
+                Bound::Unbounded => true,
+            })
+        }
+
+        /// Sum of multiples
+        ///
+        /// Sum all unique multiples of the input factors that are below the max value, in closed form.
+        /// Returns an overflow error when the sum does not fit in T.
+        #[allow(dead_code)]
+        pub fn sum_of_multiples<T>(factors: Vec<T>, max: T) -> Result<T>
+        where
+            T: $t,
+        {
+            sum_of_powers_of_multiples(factors, max, 1)
+        }
+
+        /// Count of multiples
+        ///
+        /// Count the unique multiples of the input factors that are below the max value, in closed form
+        #[allow(dead_code)]
+        pub fn count_of_multiples<T>(factors: Vec<T>, max: T) -> Result<T>
+        where
+            T: $t,
+        {
+            sum_of_powers_of_multiples(factors, max, 0)
+        }
+
+        /// Sum of powers of multiples
+        ///
+        /// Sum the k-th powers of all unique multiples of the input factors that are below the max value.
+        ///
+        /// The multiples of d below max are d, 2d, ..., md with m = (max - 1) / d, whose k-th powers sum to
+        /// d^k * (1^k + ... + m^k). The multiples of several factors are combined by inclusion-exclusion
+        /// over the lcm of every subset of factors, skipping every superset of a subset whose lcm is already
+        /// above the maximum. Positive and negative terms are accumulated separately, so an overflow error
+        /// is returned when either total does not fit in T.
+        #[allow(dead_code)]
+        pub fn sum_of_powers_of_multiples<T>(factors: Vec<T>, max: T, k: u32) -> Result<T>
+        where
+            T: $t,
+        {
+            let overflow = || {
+                NumberTheoryErr::Overflow(message!(
+                    "Sum of powers of multiples overflows {}",
+                    type_name::<T>()
+                ))
+            };
+            if max <= T::one() {
+                return Ok(T::zero());
+            }
+            let limit = max - T::one();
+
+            // Keep the distinct factors that are not multiples of a smaller factor, their multiples are already counted
+            let mut candidates: Vec<T> = factors
+                .into_iter()
+                .map(abs)
+                .filter(|factor| *factor != T::zero())
+                .collect();
+            candidates.sort();
+            let mut reduced: Vec<T> = vec![];
+            for factor in candidates {
+                if reduced.iter().all(|kept| factor % *kept != T::zero()) {
+                    reduced.push(factor);
+                }
+            }
+
+            // Depth first search over subsets, each entry is (next factor index, lcm of the subset, subset size is odd)
+            let (mut positive, mut negative) = (T::zero(), T::zero());
+            let mut stack = vec![(0, T::one(), false)];
+            while let Some((start, lcm, odd)) = stack.pop() {
+                for (i, factor) in reduced.iter().enumerate().skip(start) {
+                    // An lcm that overflows is certainly above the limit
+                    let lcm = match (lcm / lcm.gcd(*factor)).checked_mul(factor) {
+                        Some(lcm) if lcm <= limit => lcm,
+                        _ => continue,
+                    };
+                    let term = checked_pow(lcm, k as usize)
+                        .and_then(|power| power.checked_mul(&power_sum(limit / lcm, k)?))
+                        .ok_or_else(overflow)?;
+                    if odd {
+                        negative = negative.checked_add(&term).ok_or_else(overflow)?;
                     } else {
-                        break 'find;
+                        positive = positive.checked_add(&term).ok_or_else(overflow)?;
                     }
-                    i += T::one();
+                    stack.push((i + 1, lcm, !odd));
                 }
             }
-            multiples.sort();
-            multiples.dedup();
-            multiples
+            Ok(positive - negative)
         }
 
-        /// Sum of multiples
+        /// Sum of the k-th powers 1^k + 2^k + ... + m^k, or None on overflow
         ///
-        /// Sum all unique multiples of the input factors that are below the max value
-        #[allow(dead_code)]
-        pub fn sum_of_multiples<T>(factors: Vec<T>, max: T) -> T
+        /// Uses closed forms for k < 4, dividing before multiplying so that no intermediate exceeds the sum.
+        /// Higher powers use the recurrence from expanding the telescoping sum of (i + 1)^(k + 1) - i^(k + 1):
+        /// (k + 1) S_k = (m + 1)^(k + 1) - 1 - sum of C(k + 1, j) S_j for j < k. When (m + 1)^(k + 1) overflows
+        /// but m^(k + 1) / (k + 1), a lower bound on the sum, still fits, the powers are summed directly.
+        fn power_sum<T>(m: T, k: u32) -> Option<T>
         where
             T: $t,
         {
-            multiples(factors, max)
-                .into_iter()
-                .fold(T::zero(), |acc, x| acc + x)
+            let one = T::one();
+            let two = one + one;
+            let three = two + one;
+            match k {
+                0 => Some(m),
+                // m(m + 1) / 2, halving the even factor first
+                1 => {
+                    if m % two == T::zero() {
+                        (m / two).checked_mul(&(m + one))
+                    } else {
+                        m.checked_mul(&((m + one) / two))
+                    }
+                }
+                // m(m + 1)(2m + 1) / 6, halving the even factor of m(m + 1) and dividing out 3 from whichever
+                // factor it divides
+                2 => {
+                    let (mut a, mut b, mut c) = (m, m + one, m.checked_add(&m)?.checked_add(&one)?);
+                    if a % two == T::zero() {
+                        a /= two;
+                    } else {
+                        b /= two;
+                    }
+                    if a % three == T::zero() {
+                        a /= three;
+                    } else if b % three == T::zero() {
+                        b /= three;
+                    } else {
+                        c /= three;
+                    }
+                    a.checked_mul(&b)?.checked_mul(&c)
+                }
+                // (m(m + 1) / 2)^2
+                3 => {
+                    let triangle = power_sum(m, 1)?;
+                    triangle.checked_mul(&triangle)
+                }
+                _ => power_sum_recurrence(m, k).or_else(|| {
+                    let power = checked_pow(m, k as usize)?;
+                    if power / T::from_u32(k + 1)? > T::max_value() / m {
+                        return None;
+                    }
+                    let (mut sum, mut i) = (T::zero(), one);
+                    while i <= m {
+                        sum = sum.checked_add(&checked_pow(i, k as usize)?)?;
+                        i += one;
+                    }
+                    Some(sum)
+                }),
+            }
+        }
+
+        /// Sum of the k-th powers by the recurrence over S_0, ..., S_(k - 1), or None when any term overflows
+        fn power_sum_recurrence<T>(m: T, k: u32) -> Option<T>
+        where
+            T: $t,
+        {
+            let one = T::one();
+            let mut sums = vec![m];
+            for power in 1..=k {
+                // Binomial coefficients C(power + 1, j) for j = 0, ..., power - 1
+                let mut binomial = one;
+                let mut lower = T::zero();
+                for (j, sum) in sums.iter().enumerate() {
+                    lower = lower.checked_add(&binomial.checked_mul(sum)?)?;
+                    binomial = binomial.checked_mul(&T::from_u32(power + 1 - j as u32)?)?
+                        / T::from_usize(j + 1)?;
+                }
+                let upper = checked_pow(m.checked_add(&one)?, power as usize + 1)? - one;
+                sums.push((upper - lower) / T::from_u32(power + 1)?);
+            }
+            sums.pop()
         }
     };
 }
@@ -79,6 +357,7 @@ multiples!(Integer);
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rstest::rstest;
 
     #[test]
     fn test_multiples() {
@@ -91,8 +370,115 @@ mod tests {
 
     #[test]
     fn test_sum_of_multiples() {
-        assert_eq!(sum_of_multiples(vec![3, 5], 10), 23);
-        assert_eq!(sum_of_multiples(vec![2, 5], 20), 110)
+        assert_eq!(sum_of_multiples(vec![3, 5], 10).unwrap(), 23);
+        assert_eq!(sum_of_multiples(vec![2, 5], 20).unwrap(), 110)
+    }
+
+    #[rstest]
+    #[case(vec![3, 5], 10, 1, 23)]
+    #[case(vec![3, 5], 10, 2, 151)]
+    #[case(vec![3, 5], 10, 0, 4)]
+    #[case(vec![4, 6, 10], 1000, 3, 90496675648)]
+    #[case(vec![2, 3, 5, 7, 11, 13], 10000, 0, 8082)]
+    #[case(vec![6, 10, 15], 100000, 1, 1333216671)]
+    #[case(vec![3, 5, 0, -3, 15], 10, 1, 23)]
+    #[case(vec![], 10, 1, 0)]
+    #[case(vec![3, 5], 1, 1, 0)]
+    #[case(vec![3, 5], -10, 1, 0)]
+    fn test_sum_of_powers_of_multiples(
+        #[case] factors: Vec<i64>,
+        #[case] max: i64,
+        #[case] k: u32,
+        #[case] expected: i64,
+    ) {
+        assert_eq!(
+            expected,
+            sum_of_powers_of_multiples(factors, max, k).unwrap()
+        )
+    }
+
+    #[test]
+    fn test_sum_of_multiples_matches_iterator() {
+        let factor_sets = vec![
+            vec![2, 3],
+            vec![4, 6, 9],
+            vec![7, 11, 13, 77],
+            vec![12, 18, 30, 45],
+        ];
+        for factors in factor_sets {
+            for max in 0..500u64 {
+                let multiples: Vec<u64> = multiples_in(factors.clone(), ..max).collect();
+                assert_eq!(
+                    multiples.len() as u64,
+                    count_of_multiples(factors.clone(), max).unwrap()
+                );
+                for k in 0..4 {
+                    assert_eq!(
+                        multiples.iter().map(|x| x.pow(k)).sum::<u64>(),
+                        sum_of_powers_of_multiples(factors.clone(), max, k).unwrap()
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_sum_of_multiples_large() {
+        assert_eq!(
+            233333333333166666666668,
+            sum_of_multiples(vec![3u128, 5], 1000000000000).unwrap()
+        );
+        assert_eq!(
+            466666666666,
+            count_of_multiples(vec![3u64, 5], 1000000000000).unwrap()
+        );
+        assert!(matches!(
+            sum_of_multiples(vec![3u64, 5], 1000000000000),
+            Err(NumberTheoryErr::Overflow(_))
+        ));
+        assert!(matches!(
+            sum_of_powers_of_multiples(vec![3u8], 20, 2),
+            Err(NumberTheoryErr::Overflow(_))
+        ));
+    }
+
+    #[rstest]
+    #[case(9, 2, 204)]
+    #[case(6, 3, 225)]
+    #[case(4, 4, 98)]
+    #[case(3, 5, 33)]
+    fn test_sum_of_powers_of_multiples_u8_max(
+        #[case] max: u8,
+        #[case] k: u32,
+        #[case] expected: u8,
+    ) {
+        assert_eq!(
+            expected,
+            sum_of_powers_of_multiples(vec![1], max, k).unwrap()
+        );
+        assert!(matches!(
+            sum_of_powers_of_multiples(vec![1], max + 1, k),
+            Err(NumberTheoryErr::Overflow(_))
+        ));
+    }
+
+    #[rstest]
+    #[case(3810778, 2, 18446735571075162805)]
+    #[case(92682, 3, 18446425603259108841)]
+    #[case(9840, 4, 18445700051808767672)]
+    fn test_sum_of_powers_of_multiples_u64_max(
+        #[case] max: u64,
+        #[case] k: u32,
+        #[case] expected: u64,
+    ) {
+        assert_eq!(
+            expected,
+            sum_of_powers_of_multiples(vec![1], max, k).unwrap()
+        );
+        assert!(matches!(
+            sum_of_powers_of_multiples(vec![1], max + 1, k),
+            Err(NumberTheoryErr::Overflow(_))
+        ));
     }
 
     #[test]
@@ -102,4 +488,86 @@ mod tests {
             vec![300000000, 600000000, 900000000, 1200000000, 1500000000, 1800000000, 2100000000]
         );
     }
+
+    #[test]
+    fn test_multiples_of_iterator() {
+        assert_eq!(
+            vec![1, 2, 3, 4, 5],
+            MultiplesOf::new(1u64).take(5).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![250, 255],
+            MultiplesOf::new(5u8).skip(49).collect::<Vec<_>>()
+        );
+        assert_eq!(Some(12), MultiplesOf::new(-3i32).nth(3));
+        assert_eq!(None, MultiplesOf::new(0i32).next());
+    }
+
+    #[test]
+    fn test_multiples_iterator() {
+        assert_eq!(
+            vec![3, 5, 6, 9, 10, 12, 15, 18, 20, 21],
+            Multiples::new(vec![3, 5]).take(10).collect::<Vec<u64>>()
+        );
+        assert_eq!(
+            vec![15, 18, 20],
+            Multiples::new(vec![3, 5])
+                .skip(6)
+                .take(3)
+                .collect::<Vec<u64>>()
+        );
+        assert_eq!(
+            vec![2, 3, 4, 6, 8, 9, 10, 12],
+            Multiples::new(vec![2, 3, 6, 0, -2])
+                .take(8)
+                .collect::<Vec<i32>>()
+        );
+        assert_eq!(0, Multiples::<u32>::new(vec![]).count());
+        assert_eq!(0, Multiples::new(vec![0u32]).count());
+    }
+
+    #[test]
+    fn test_multiples_iterator_overflow() {
+        // Every multiple of 7 or 11 that fits in a u8, each progression stops before overflowing
+        let expected: Vec<u8> = (1..=255u8).filter(|x| x % 7 == 0 || x % 11 == 0).collect();
+        assert_eq!(expected, Multiples::new(vec![7u8, 11]).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_multiples_starting_at() {
+        assert_eq!(
+            vec![1000000000000, 1000000000002, 1000000000004],
+            Multiples::starting_at(vec![2, 5], 1000000000000u64)
+                .take(3)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![3, 5],
+            Multiples::starting_at(vec![3, 5], -10i32)
+                .take(2)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_multiples_in() {
+        assert_eq!(
+            vec![20, 21, 24, 25, 27],
+            multiples_in(vec![3, 5], 19..28).collect::<Vec<u32>>()
+        );
+        assert_eq!(
+            vec![20, 21, 24, 25, 27],
+            multiples_in(vec![3, 5], 20..=27).collect::<Vec<u32>>()
+        );
+        assert_eq!(
+            vec![3, 5, 6],
+            multiples_in(vec![3, 5], ..=6).collect::<Vec<u32>>()
+        );
+        // u64::MAX = 3 * 5 * 17 * 257 * 641 * 65537 * 6700417 is the only multiple in range
+        assert_eq!(
+            vec![u64::MAX],
+            multiples_in(vec![u64::MAX / 3, 17], u64::MAX - 16..).collect::<Vec<_>>()
+        );
+        assert_eq!(0, multiples_in(vec![3, 5], 10..10).count());
+    }
 }
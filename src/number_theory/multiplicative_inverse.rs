@@ -1,11 +1,14 @@
 //! Modular Multiplicative Inverse: ax = 1 (mod b)
 use crate::{
-    general::{errors::NumberTheoryErr, numbers::Integer},
-    number_theory::euclidean_algs::ExtendedEuclidean,
+    general::{
+        errors::{message, NumberTheoryErr},
+        numbers::Integer,
+    },
+    number_theory::{euclidean_algs::ExtendedEuclidean, modulus::Modulus},
 };
-use std::fmt::Display;
+use core::fmt::Display;
 
-type Result<T> = std::result::Result<T, NumberTheoryErr>;
+type Result<T> = core::result::Result<T, NumberTheoryErr>;
 
 /// Implement the modular multiplicative inverse operation
 pub trait MultiplicativeInverse<T> {
@@ -18,17 +21,19 @@ macro_rules! multiplicative_inverse {
         impl<T: $t + Display> MultiplicativeInverse<T> for T {
             /// Modular Multiplicative Inverse
             ///
-            /// This function returns the modular multiplicative inverse of an integer.
+            /// This function returns the modular multiplicative inverse of an integer as the least non-negative residue
             fn multiplicative_inverse(&self, other: T) -> Result<T> {
                 // Calculate the GCD and Bezout coefficients
                 let egcd = self.egcd(other);
                 if egcd.0 != T::one() {
-                    Err(NumberTheoryErr::InverseDNE(format!(
+                    Err(NumberTheoryErr::InverseDNE(message!(
                         "No modular multiplicative inverse exists {} mod {}",
-                        self, other
+                        self,
+                        other
                     )))
                 } else {
-                    Ok(egcd.1)
+                    // Bezout coefficients may be negative, reduce into [0, |other|)
+                    Ok(egcd.1.modulus(other))
                 }
             }
         }
@@ -40,12 +45,17 @@ multiplicative_inverse!(Integer);
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::number_theory::gcd::Gcd;
     use rstest::rstest;
 
     #[rstest(
         case(1, 1, 0),
         case(9, 26, 3),
         case(1073, 25, 12),
+        case(3, 7, 5),
+        case(-3, 7, 2),
+        case(3, -7, 5),
+        case(-3, -7, 2),
         #[should_panic]
         case(1073, 29, 0)
     )]
@@ -56,4 +66,19 @@ mod tests {
     ) {
         assert_eq!(expected, a.multiplicative_inverse(b).unwrap())
     }
+
+    #[test]
+    fn isize_multiplicative_inverse_negative_range_test() {
+        for m in [-13isize, -12, 12, 13] {
+            for a in -100isize..=100 {
+                match a.multiplicative_inverse(m) {
+                    Ok(inv) => {
+                        assert!(0 <= inv && inv < m.abs());
+                        assert_eq!(1, (a * inv).rem_euclid(m.abs()));
+                    }
+                    Err(_) => assert_ne!(1, a.gcd(m)),
+                }
+            }
+        }
+    }
 }
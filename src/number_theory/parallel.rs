@@ -0,0 +1,119 @@
+//! Parallel Bulk Operations (feature "rayon")
+//!
+//! Rayon versions of the operations that are run over many inputs at once. The work is split into
+//! independent pieces and the results are collected in input order, so every function returns exactly
+//! what its sequential counterpart does, whatever the number of threads.
+use crate::{
+    general::numbers::Integer,
+    number_theory::{
+        euler_totient::EulerTotient,
+        primality::Primality,
+        prime_counting::{sieve_block, sieving_primes, SIEVE_BLOCK},
+    },
+};
+use rayon::prelude::*;
+
+/// Prime factorizations of every value, in the same order as the input
+pub fn par_prime_factorizations<T: Integer>(values: &[T]) -> Vec<Vec<T>> {
+    values.par_iter().map(|n| n.prime_factorization()).collect()
+}
+
+/// Euler totients φ(n) for every n in [low, high]
+pub fn par_euler_totients<T: Integer>(low: T, high: T) -> Vec<T> {
+    let mut values = vec![];
+    let mut n = low;
+    while n <= high {
+        values.push(n);
+        if n == high {
+            break;
+        }
+        n += T::one();
+    }
+    values.par_iter().map(|n| n.euler_totient()).collect()
+}
+
+/// The blocks [start, end] covering [max(low, 2), high] that the segmented sieve works through
+fn blocks(low: u64, high: u64) -> Vec<(u64, u64)> {
+    let mut blocks = vec![];
+    let mut start = low.max(2);
+    while start <= high {
+        let end = start.saturating_add(SIEVE_BLOCK - 1).min(high);
+        blocks.push((start, end));
+        if end == high {
+            break;
+        }
+        start = end + 1;
+    }
+    blocks
+}
+
+/// Parallel Segmented Sieve
+///
+/// Every prime in [low, high], the same as segmented_sieve but with the blocks sieved in parallel
+pub fn par_segmented_sieve(low: u64, high: u64) -> Vec<u64> {
+    let sieving_primes = sieving_primes(high);
+    blocks(low, high)
+        .into_par_iter()
+        .flat_map_iter(|(start, end)| sieve_block(start, end, &sieving_primes))
+        .collect()
+}
+
+/// Parallel Interval Prime Count
+///
+/// The number of primes in [low, high], counted block by block in parallel
+pub fn par_prime_count(low: u64, high: u64) -> u64 {
+    let sieving_primes = sieving_primes(high);
+    blocks(low, high)
+        .into_par_iter()
+        .map(|(start, end)| sieve_block(start, end, &sieving_primes).len() as u64)
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::number_theory::prime_counting::{prime_count, segmented_sieve};
+    use rstest::rstest;
+
+    #[test]
+    fn par_prime_factorizations_test() {
+        let values: Vec<i64> = (-500..500).chain([65536, 999983 * 3, 1 << 40]).collect();
+        let expected: Vec<Vec<i64>> = values.iter().map(|n| n.prime_factorization()).collect();
+        assert_eq!(expected, par_prime_factorizations(&values));
+    }
+
+    #[rstest]
+    #[case(0, 1000)]
+    #[case(-50, 50)]
+    #[case(100, 99)]
+    #[case(120, 127)]
+    fn i64_par_euler_totients_test(#[case] low: i64, #[case] high: i64) {
+        let expected: Vec<i64> = (low..=high).map(|n| n.euler_totient()).collect();
+        assert_eq!(expected, par_euler_totients(low, high));
+        if let (Ok(low), Ok(high)) = (i8::try_from(low), i8::try_from(high)) {
+            let expected: Vec<i8> = (low..=high).map(|n| n.euler_totient()).collect();
+            assert_eq!(expected, par_euler_totients(low, high));
+        }
+    }
+
+    #[rstest]
+    #[case(0, 100)]
+    #[case(0, 3 * SIEVE_BLOCK + 17)]
+    #[case(1000000000, 1000000000 + 2 * SIEVE_BLOCK)]
+    #[case(50, 40)]
+    #[case(1000000000000, 1000000010000)]
+    fn par_segmented_sieve_test(#[case] low: u64, #[case] high: u64) {
+        let expected = segmented_sieve(low, high);
+        assert_eq!(expected, par_segmented_sieve(low, high));
+        assert_eq!(expected.len() as u64, par_prime_count(low, high));
+    }
+
+    #[test]
+    fn par_prime_count_test() {
+        assert_eq!(prime_count(10000000), par_prime_count(0, 10000000));
+        assert_eq!(
+            prime_count(20000000) - prime_count(9999999),
+            par_prime_count(10000000, 20000000)
+        );
+    }
+}
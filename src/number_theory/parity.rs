@@ -1,5 +1,7 @@
 //! Parity Trait
 use crate::general::numbers::Integer;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 /// Implement parity checking
 pub trait Parity<T> {
@@ -8,6 +10,8 @@ pub trait Parity<T> {
     fn is_even(&self) -> bool;
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum NumParity {
     Even,
     Odd,
@@ -102,4 +106,14 @@ mod tests {
     fn isize_is_odd_test(#[case] a: isize, #[case] expected: bool) {
         assert_eq!(expected, a.is_odd())
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn num_parity_serde_test() {
+        for parity in [NumParity::Even, NumParity::Odd] {
+            let json = serde_json::to_string(&parity).unwrap();
+            assert_eq!(parity, serde_json::from_str(&json).unwrap());
+        }
+        assert_eq!(r#""Odd""#, serde_json::to_string(&7.parity()).unwrap());
+    }
 }
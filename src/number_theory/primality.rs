@@ -1,18 +1,33 @@
 //! Primality Trait
+#[cfg(feature = "alloc")]
+use crate::number_theory::{
+    euler_totient::EulerTotient, integer_roots::IntegerRoots,
+    modular_exponentiation::ModularExponentiation,
+};
 use crate::{
-    general::{errors::NumberTheoryErr, numbers::Integer},
+    general::{
+        errors::NumberTheoryErr,
+        numbers::{abs, Integer},
+    },
     number_theory::{factorial::Factorial, gcd::Gcd},
 };
-use std::fmt::Display;
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
+use core::fmt::Display;
 
-type Result<T> = std::result::Result<T, NumberTheoryErr>;
+type Result<T> = core::result::Result<T, NumberTheoryErr>;
 
 /// Primality Trait
 ///
 /// Implements various functions covering primality tests and prime factorizations
+///
+/// Negative integers follow the ring convention: -p is prime exactly when p is prime, since the two
+/// only differ by the unit -1. The prime factorization of a negative integer starts with -1, and the
+/// product of the returned factors is always the input (the factorization of 0 is [0]).
 pub trait Primality<T> {
     fn primality(self) -> bool;
     fn relative_primality(self, other: T) -> bool;
+    #[cfg(feature = "alloc")]
     fn prime_factorization(self) -> Vec<T>;
 }
 
@@ -23,6 +38,16 @@ pub trait WilsonsPrimality<T> {
     fn wilson(self) -> Result<bool>;
 }
 
+/// AKS Primality Trait
+///
+/// The Agrawal-Kayal-Saxena test, the first deterministic, unconditional and polynomial time
+/// primality test. It is far slower than trial division for every integer that fits in a machine word,
+/// and is here as a faithful reference implementation of the paper rather than a practical test.
+#[cfg(feature = "alloc")]
+pub trait AksPrimality<T> {
+    fn aks(self) -> bool;
+}
+
 #[macro_export]
 macro_rules! primality {
     ($t: ident) => {
@@ -38,15 +63,23 @@ macro_rules! primality {
                 let one = T::one();
                 let two = T::one() + T::one();
                 let three = two + T::one();
-                if self <= three {
-                    return (self > one);
-                } else if self % two == zero || self % three == zero {
+
+                // Even numbers are only prime as 2 or -2. Checked before taking the absolute value
+                // since T::min_value() is even and has no representable absolute value.
+                if self % two == zero {
+                    return self == two || (self < zero && self + two == zero);
+                }
+                let n = abs(self);
+                if n <= three {
+                    return (n > one);
+                } else if n % three == zero {
                     return false;
                 }
 
+                // Compare against n / b rather than b * b <= n to avoid overflow near T::max_value()
                 let mut b = three + T::one() + T::one();
-                while b * b <= self {
-                    if self % b == zero || self % (b + two) == zero {
+                while b <= n / b {
+                    if n % b == zero || n % (b + two) == zero {
                         return false;
                     }
                     b = b + three + three;
@@ -65,6 +98,7 @@ macro_rules! primality {
             /// Prime factorization
             ///
             /// Return the prime factorization of the input integer
+            #[cfg(feature = "alloc")]
             fn prime_factorization(self) -> Vec<T> {
                 // Initialize
                 let mut n = self;
@@ -73,16 +107,29 @@ macro_rules! primality {
                 let three = T::one() + T::one() + T::one();
                 let mut output: Vec<T> = vec![];
 
+                // Zero has no prime factorization, return it unchanged so the product is preserved
+                if n == zero {
+                    return vec![zero];
+                }
+
+                // Negative numbers carry the unit -1 as their first factor
+                if n < zero {
+                    output.push(zero - T::one());
+                }
+
                 // While even, divide out 2
                 while n % two == zero {
                     output.push(two);
                     n /= two;
                 }
 
-                // Odd numbers greater than n
-                let limit = n / two;
+                // n is now odd, so its absolute value is representable even for T::min_value()
+                n = abs(n);
+
+                // Odd trial divisors up to √n, where x <= n / x avoids overflowing x * x. Any factor
+                // left over once x passes √n is prime.
                 let mut x = three;
-                while x <= limit {
+                while x <= n / x {
                     while n % x == zero {
                         output.push(x);
                         n /= x;
@@ -112,6 +159,20 @@ macro_rules! wilsons_primality {
             ///
             /// Only use this if you are using small integers. Actually, don't use this at all. Wilson's theorem is never practical to compute but has implications in theory
             fn wilson(self) -> Result<bool> {
+                let zero = T::zero();
+                let two = T::one() + T::one();
+
+                // Follow the primality convention for negatives, -p is prime exactly when p is prime
+                if self < zero {
+                    // T::min_value() is even and has no representable absolute value
+                    if self % two == zero {
+                        return Ok(self + two == zero);
+                    }
+                    return (zero - self).wilson();
+                } else if self < two {
+                    return Ok(false);
+                }
+
                 // Rust can perform a remainder operation, but not a modular operation
                 match (self - T::one()).factorial() {
                     Ok(val) => Ok((val % self) + T::one() + T::one() == ((T::one()) % self) + self),
@@ -122,8 +183,154 @@ macro_rules! wilsons_primality {
     };
 }
 
+#[macro_export]
+macro_rules! aks_primality {
+    ($t: ident) => {
+        impl<T> AksPrimality<T> for T
+        where
+            T: $t,
+        {
+            /// AKS Primality Test
+            ///
+            /// The steps of "PRIMES is in P", with log2(n) rounded up to the bit length of n:
+            ///
+            /// 1. If n = a^b for b > 1, n is composite
+            /// 2. Find the smallest r with ord_r(n) > log2(n)^2
+            /// 3. If 1 < gcd(a, n) < n for some a <= r, n is composite
+            /// 4. If n <= r, n is prime
+            /// 5. If (X + a)^n != X^n + a (mod X^r - 1, n) for some a <= √φ(r) log2(n), n is composite
+            /// 6. Otherwise n is prime
+            fn aks(self) -> bool {
+                let zero = T::zero();
+                let one = T::one();
+                let two = one + one;
+
+                // Follow the primality convention for negatives, T::min_value() is even
+                if self < zero {
+                    if self % two == zero {
+                        return self + two == zero;
+                    }
+                    return (zero - self).aks();
+                } else if self < two {
+                    return false;
+                }
+                let n = self;
+
+                // Step 1
+                if n.is_perfect_power() {
+                    return false;
+                }
+
+                // Step 2, with n mod r taken as n when r does not fit in T
+                let log = (core::mem::size_of::<T>() * 8) as u64 - n.leading_zeros() as u64;
+                let residue = |r: u64| match T::from(r) {
+                    Some(r) => (n % r).to_u64().unwrap(),
+                    None => n.to_u64().unwrap(),
+                };
+                let order_exceeds = |r: u64| {
+                    let base = residue(r);
+                    let mut power = 1;
+                    (1..=log * log).all(|_| {
+                        power = power * base % r;
+                        power != 1
+                    })
+                };
+                let r = (2..)
+                    .find(|r: &u64| residue(*r).gcd(*r) == 1 && order_exceeds(*r))
+                    .unwrap();
+
+                // Step 3
+                let mut a = two;
+                while a <= n - one && a.to_u64().is_some_and(|a| a <= r) {
+                    let g = a.gcd(n);
+                    if g > one && g < n {
+                        return false;
+                    }
+                    a += one;
+                }
+
+                // Step 4
+                if n.to_u64().is_some_and(|n| n <= r) {
+                    return true;
+                }
+
+                // Step 5
+                let limit = (r.euler_totient() * log * log).isqrt();
+                let x_n = residue(r) as usize;
+                for a in 1..=limit {
+                    let a = T::from(a).unwrap() % n;
+                    let mut expected = vec![zero; r as usize];
+                    expected[0] = a;
+                    expected[x_n] = (expected[x_n] + one) % n;
+                    if polynomial_pow(a, n, r as usize) != expected {
+                        return false;
+                    }
+                }
+
+                // Step 6
+                true
+            }
+        }
+
+        /// (X + a)^n (mod X^r - 1, n) by square-and-multiply, as the coefficients of 1, X, ..., X^(r - 1)
+        fn polynomial_pow<T: $t>(a: T, n: T, r: usize) -> Vec<T> {
+            let mut result = vec![T::zero(); r];
+            result[0] = T::one();
+            let mut base = vec![T::zero(); r];
+            base[0] = a;
+            base[1 % r] = (base[1 % r] + T::one()) % n;
+            let mut exponent = n;
+            while exponent > T::zero() {
+                if exponent & T::one() == T::one() {
+                    result = polynomial_mul(&result, &base, n);
+                }
+                base = polynomial_mul(&base, &base, n);
+                exponent = exponent >> 1;
+            }
+            result
+        }
+
+        /// Product of two polynomials modulo X^r - 1 and n, X^r wraps around to 1
+        ///
+        /// Below 2^32 the products of coefficients are summed in u128 and reduced once per coefficient
+        fn polynomial_mul<T: $t>(f: &[T], g: &[T], n: T) -> Vec<T> {
+            let r = f.len();
+            if let Some(m) = n.to_u64().filter(|m| *m <= u32::MAX as u64) {
+                let f: Vec<u128> = f.iter().map(|a| a.to_u128().unwrap()).collect();
+                let g: Vec<u128> = g.iter().map(|b| b.to_u128().unwrap()).collect();
+                let mut product = vec![0u128; r];
+                for (i, a) in f.iter().enumerate().filter(|(_, a)| **a != 0) {
+                    for (j, b) in g.iter().enumerate() {
+                        product[(i + j) % r] += a * b;
+                    }
+                }
+                return product
+                    .into_iter()
+                    .map(|c| T::from_u128(c % m as u128).unwrap())
+                    .collect();
+            }
+
+            let mut product = vec![T::zero(); r];
+            for (i, a) in f.iter().enumerate().filter(|(_, a)| **a != T::zero()) {
+                for (j, b) in g.iter().enumerate().filter(|(_, b)| **b != T::zero()) {
+                    let k = (i + j) % r;
+                    let term = a.mod_mul(*b, n);
+                    product[k] = if product[k] >= n - term {
+                        product[k] - (n - term)
+                    } else {
+                        product[k] + term
+                    };
+                }
+            }
+            product
+        }
+    };
+}
+
 primality!(Integer);
 wilsons_primality!(Integer);
+#[cfg(feature = "alloc")]
+aks_primality!(Integer);
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -152,6 +359,11 @@ mod tests {
         case::non_prime_1(15, false),
         case::non_prime_2(4, false),
         case::non_prime_3(9, false),
+        case::negative_prime_1(-2, true),
+        case::negative_prime_2(-5, true),
+        case::negative_non_prime_1(-1, false),
+        case::negative_non_prime_2(-9, false),
+        case::zero(0, false),
         #[should_panic]
         case::should_panic_1(22, false),
         #[should_panic]
@@ -185,6 +397,16 @@ mod tests {
     #[case(15, false)]
     #[case(30, false)]
     #[case(225, false)]
+    #[case(0, false)]
+    #[case(1, false)]
+    #[case(-1, false)]
+    #[case(-2, true)]
+    #[case(-3, true)]
+    #[case(-4, false)]
+    #[case(-225, false)]
+    #[case(-7919, true)]
+    #[case(isize::MIN, false)]
+    #[case(isize::MAX, false)]
     fn isize_primality_test(#[case] a: isize, #[case] expected: bool) {
         assert_eq!(expected, a.primality())
     }
@@ -197,6 +419,8 @@ mod tests {
     #[case(6, vec![2, 3])]
     #[case(9, vec![3, 3])]
     #[case(315, vec![3, 3, 5, 7])]
+    #[case(2 * 1000000000039, vec![2, 1000000000039])]
+    #[case(1000003 * 1000033, vec![1000003, 1000033])]
     fn usize_prime_factorization_test(#[case] a: usize, #[case] expected: Vec<usize>) {
         assert_eq!(expected, a.prime_factorization())
     }
@@ -209,7 +433,107 @@ mod tests {
     #[case(6, vec![2, 3])]
     #[case(9, vec![3, 3])]
     #[case(315, vec![3, 3, 5, 7])]
+    #[case(0, vec![0])]
+    #[case(1, vec![])]
+    #[case(-1, vec![-1])]
+    #[case(-2, vec![-1, 2])]
+    #[case(-315, vec![-1, 3, 3, 5, 7])]
+    #[case(i8::MIN as isize, vec![-1, 2, 2, 2, 2, 2, 2, 2])]
     fn isize_prime_factorization_test(#[case] a: isize, #[case] expected: Vec<isize>) {
         assert_eq!(expected, a.prime_factorization())
     }
+
+    #[test]
+    fn u64_prime_factorization_max_test() {
+        // Trial division stops at the square root of what is left
+        assert_eq!(
+            vec![3, 5, 17, 257, 641, 65537, 6700417],
+            u64::MAX.prime_factorization()
+        );
+        assert_eq!(
+            vec![7, 7, 73, 127, 337, 92737, 649657],
+            i64::MAX.prime_factorization()
+        );
+    }
+
+    #[test]
+    fn i8_prime_factorization_min_test() {
+        assert_eq!(vec![-1, 2, 2, 2, 2, 2, 2, 2], i8::MIN.prime_factorization());
+    }
+
+    #[test]
+    fn isize_negative_range_test() {
+        for n in -1000isize..=1000 {
+            let factors = n.prime_factorization();
+            assert_eq!(n, factors.iter().product::<isize>());
+            assert_eq!(n < 0, factors.first() == Some(&-1));
+            assert!(factors
+                .iter()
+                .skip_while(|f| **f <= 0)
+                .all(|f| f.primality()));
+            assert_eq!(n.primality(), (-n).primality());
+            assert_eq!(
+                n.abs() > 1 && factors.len() == 1 + (n < 0) as usize,
+                n.primality()
+            );
+        }
+    }
+
+    #[rstest]
+    #[case(-7, true)]
+    #[case(-4, false)]
+    #[case(0, false)]
+    #[case(1, false)]
+    #[case(2, true)]
+    #[case(31, true)]
+    #[case(343, false)]
+    #[case(561, false)]
+    #[case(1729, false)]
+    #[case(7919, true)]
+    #[case(41041, false)]
+    fn i64_aks_test(#[case] n: i64, #[case] expected: bool) {
+        assert_eq!(expected, n.aks())
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn aks_miller_rabin_agreement_test() {
+        // Small n are decided by step 4 since n <= r, the polynomial congruences are only reached
+        // beyond that
+        use crate::number_theory::factorization::BigPrimality;
+        use num::BigUint;
+        for n in (0u32..400)
+            .chain(7900..7920)
+            .chain([10007, 10009, 41041, 75361])
+        {
+            assert_eq!(BigUint::from(n).is_probable_prime(), n.aks(), "{}", n);
+        }
+    }
+
+    #[test]
+    fn aks_small_types_test() {
+        for n in i8::MIN..=i8::MAX {
+            assert_eq!(n.primality(), n.aks());
+        }
+        for n in u8::MIN..=u8::MAX {
+            assert_eq!(n.primality(), n.aks());
+        }
+    }
+
+    #[test]
+    fn aks_polynomial_congruence_test() {
+        // (X + a)^p = X^p + a (mod X^r - 1, p) for every prime p and any r, and fails for the
+        // composite 3p
+        let p = (1u64 << 61) - 1;
+        for r in [2, 7, 31] {
+            let mut expected = vec![0; r];
+            expected[0] = 5;
+            expected[(p % r as u64) as usize] += 1;
+            assert_eq!(expected, polynomial_pow(5, p, r));
+            let mut expected = vec![0; r];
+            expected[0] = 5;
+            expected[(3 * p % r as u64) as usize] += 1;
+            assert_ne!(expected, polynomial_pow(5, 3 * p, r));
+        }
+    }
 }
@@ -0,0 +1,606 @@
+//! Prime Counting: π(x) and sums of primes
+//!
+//! Counts the primes up to x without enumerating them. Legendre's formula and Meissel-Lehmer both
+//! count the integers left over by a partial sieve, φ(x, a), and correct for what the partial sieve
+//! misses. Lucy_Hedgehog's method instead runs the sieve of Eratosthenes over the O(√x) values of x / n,
+//! which gives sums of primes for free.
+use crate::{
+    general::{
+        errors::{message, NumberTheoryErr},
+        numbers::Integer,
+    },
+    number_theory::{integer_roots::IntegerRoots, primality::Primality},
+};
+use alloc::{vec, vec::Vec};
+use core::any::type_name;
+
+type Result<T> = core::result::Result<T, NumberTheoryErr>;
+
+/// Primorial of the primes handled by the φ(x, a) lookup table, 2 * 3 * 5 * 7 * 11 * 13
+const PRIMORIAL: u64 = 30030;
+const PRIMORIAL_PRIMES: usize = 6;
+
+/// Below this bound π(x) is answered with a plain sieve
+const SIEVE_THRESHOLD: u64 = 1 << 16;
+
+/// Sieve of Eratosthenes: every prime up to and including limit
+fn primes_up_to(limit: u64) -> Vec<u64> {
+    if limit < 2 {
+        return vec![];
+    }
+    let limit = limit as usize;
+    let mut is_prime = vec![true; limit + 1];
+    is_prime[0] = false;
+    is_prime[1] = false;
+    let mut p = 2;
+    while p * p <= limit {
+        if is_prime[p] {
+            (p * p..=limit).step_by(p).for_each(|m| is_prime[m] = false);
+        }
+        p += 1;
+    }
+    is_prime
+        .iter()
+        .enumerate()
+        .filter(|(_, prime)| **prime)
+        .map(|(p, _)| p as u64)
+        .collect()
+}
+
+/// Block length of the segmented sieve, small enough to stay in cache
+pub(crate) const SIEVE_BLOCK: u64 = 1 << 18;
+
+/// The primes needed to sieve any block up to high, those up to √high
+pub(crate) fn sieving_primes(high: u64) -> Vec<u64> {
+    primes_up_to(high.isqrt())
+}
+
+/// Every prime in [start, end] for 2 <= start, given at least the primes up to √end
+pub(crate) fn sieve_block(start: u64, end: u64, sieving_primes: &[u64]) -> Vec<u64> {
+    let mut is_prime = vec![true; (end - start + 1) as usize];
+    for &p in sieving_primes.iter().take_while(|p| **p <= end / **p) {
+        // First multiple of p in the block, never p itself
+        let first = (p * p).max(start.div_ceil(p) * p);
+        (first..=end)
+            .step_by(p as usize)
+            .for_each(|m| is_prime[(m - start) as usize] = false);
+    }
+    is_prime
+        .iter()
+        .enumerate()
+        .filter(|(_, prime)| **prime)
+        .map(|(i, _)| start + i as u64)
+        .collect()
+}
+
+/// Segmented Sieve
+///
+/// Every prime in [low, high], sieving the interval in blocks with the primes up to √high so that
+/// memory use depends on the block size rather than on high
+pub fn segmented_sieve(low: u64, high: u64) -> Vec<u64> {
+    let low = low.max(2);
+    if high < low {
+        return vec![];
+    }
+    let sieving_primes = sieving_primes(high);
+    let mut primes = vec![];
+    let mut start = low;
+    loop {
+        let end = start.saturating_add(SIEVE_BLOCK - 1).min(high);
+        primes.extend(sieve_block(start, end, &sieving_primes));
+        if end == high {
+            return primes;
+        }
+        start = end + 1;
+    }
+}
+
+/// Tables shared by Legendre's formula and Meissel-Lehmer
+///
+/// The primes up to a little past √x, constant time π(y) for y up to a limit through an odd-only bitset
+/// with running counts, and φ(y, a) for a <= 6 through the periodicity of the primorial.
+struct PrimeTable {
+    primes: Vec<u64>,
+    limit: u64,
+    bits: Vec<u64>,
+    counts: Vec<u32>,
+    phi_table: Vec<Vec<u16>>,
+}
+
+impl PrimeTable {
+    fn new(prime_limit: u64, pi_limit: u64) -> Self {
+        // Odd-only bitset, bit k stands for 2k + 1
+        let pi_limit = pi_limit.max(2);
+        let odd_count = pi_limit.div_ceil(2) as usize;
+        let mut bits = vec![u64::MAX; odd_count.div_ceil(64)];
+        bits[0] &= !1;
+        let mut p = 3;
+        while p * p <= pi_limit {
+            let k = (p / 2) as usize;
+            if bits[k / 64] >> (k % 64) & 1 == 1 {
+                let mut m = (p * p / 2) as usize;
+                while m < odd_count {
+                    bits[m / 64] &= !(1 << (m % 64));
+                    m += p as usize;
+                }
+            }
+            p += 2;
+        }
+        let mut counts = Vec::with_capacity(bits.len());
+        let mut count = 0;
+        for word in &bits {
+            counts.push(count);
+            count += word.count_ones();
+        }
+
+        // phi_table[a][r] = φ(r, a), the integers in [1, r] with no prime factor among the first a primes
+        let small_primes = [2, 3, 5, 7, 11, 13];
+        let mut phi_table = vec![(0..PRIMORIAL as u16).collect::<Vec<u16>>()];
+        for p in small_primes {
+            let previous = phi_table.last().unwrap();
+            let mut row = Vec::with_capacity(PRIMORIAL as usize);
+            let mut count = 0;
+            for r in 0..PRIMORIAL as usize {
+                if r > 0 && previous[r] != previous[r - 1] && !(r as u64).is_multiple_of(p) {
+                    count += 1;
+                }
+                row.push(count);
+            }
+            phi_table.push(row);
+        }
+
+        PrimeTable {
+            // One prime past √x is needed for the stopping rule in φ
+            primes: primes_up_to(2 * prime_limit.max(2)),
+            limit: pi_limit,
+            bits,
+            counts,
+            phi_table,
+        }
+    }
+
+    /// π(y) for y up to the table limit
+    fn pi(&self, y: u64) -> u64 {
+        debug_assert!(y <= self.limit);
+        if y < 2 {
+            return 0;
+        }
+        let k = ((y - 1) / 2) as usize;
+        let mask = u64::MAX >> (63 - k % 64);
+        1 + (self.counts[k / 64] + (self.bits[k / 64] & mask).count_ones()) as u64
+    }
+
+    /// Legendre's φ(y, a): the integers in [1, y] with no prime factor among the first a primes
+    fn phi(&self, y: u64, a: usize) -> u64 {
+        if a <= PRIMORIAL_PRIMES {
+            return self.small_phi(y, a);
+        }
+        if y < self.primes[a - 1] {
+            return y.min(1);
+        }
+        // Every composite up to y has a prime factor at most √y, so the survivors are 1 and the primes
+        // above p_a once p_{a+1}^2 > y
+        let next = self.primes[a];
+        if y <= self.limit && y / next < next {
+            return self.pi(y) + 1 - a as u64;
+        }
+
+        // φ(y, a) = φ(y, 6) - Σ φ(y / p_i, i - 1) over i = 7..=a
+        let mut result = self.small_phi(y, PRIMORIAL_PRIMES);
+        for i in PRIMORIAL_PRIMES + 1..=a {
+            let p = self.primes[i - 1];
+            let quotient = y / p;
+            if quotient < p {
+                // φ(y / p_j, j - 1) is 1 from here on for every p_j <= y, and 0 after that
+                let remaining = self.primes[i - 1..a].partition_point(|q| *q <= y);
+                return result - remaining as u64;
+            }
+            result -= self.phi(quotient, i - 1);
+        }
+        result
+    }
+
+    /// φ(y, a) for a <= 6, which is periodic modulo the primorial
+    fn small_phi(&self, y: u64, a: usize) -> u64 {
+        if a == 0 {
+            return y;
+        }
+        let row = &self.phi_table[a];
+        (y / PRIMORIAL) * row[PRIMORIAL as usize - 1] as u64 + row[(y % PRIMORIAL) as usize] as u64
+    }
+}
+
+/// Legendre's Formula
+///
+/// π(x) = φ(x, a) + a - 1 where a = π(√x), since the integers surviving a sieve by the primes up to √x
+/// are 1 and the primes above √x
+pub fn legendre(x: u64) -> u64 {
+    if x < SIEVE_THRESHOLD {
+        return primes_up_to(x).len() as u64;
+    }
+    let root = x.isqrt();
+    let table = PrimeTable::new(root, root);
+    let a = table.pi(root);
+    table.phi(x, a as usize) + a - 1
+}
+
+/// Lucy_Hedgehog's Method
+///
+/// Returns (π(x), the sum of the primes up to x) in O(x^(3/4)) time and O(√x) space. Starting from
+/// S(v) = v - 1 integers in [2, v] for every v = x / n, each prime p removes the integers whose smallest
+/// prime factor is p: S(v) -= S(v / p) - S(p - 1). The sums are tracked the same way, weighted by p.
+pub fn lucy_hedgehog(x: u64) -> (u64, u128) {
+    if x < 2 {
+        return (0, 0);
+    }
+    let r = x.isqrt();
+    let r_usize = r as usize;
+    let triangle = |v: u64| v as u128 * (v as u128 + 1) / 2 - 1;
+
+    // Values v <= r live in the small tables indexed by v, values v = x / i in the large ones indexed by i
+    let mut small_count: Vec<u64> = (0..=r).map(|v| v.saturating_sub(1)).collect();
+    let mut small_sum: Vec<u128> = (0..=r)
+        .map(|v| if v == 0 { 0 } else { triangle(v) })
+        .collect();
+    let mut large_count: Vec<u64> = (0..=r)
+        .map(|i| x.checked_div(i).map_or(0, |v| v - 1))
+        .collect();
+    let mut large_sum: Vec<u128> = (0..=r)
+        .map(|i| x.checked_div(i).map_or(0, triangle))
+        .collect();
+
+    for p in 2..=r_usize {
+        if small_count[p] == small_count[p - 1] {
+            continue;
+        }
+        let (below_count, below_sum) = (small_count[p - 1], small_sum[p - 1]);
+        let square = (p * p) as u64;
+        let p_wide = p as u128;
+
+        // Large values first, each one reads smaller values that are still from the previous prime
+        for i in 1..=r_usize.min((x / square) as usize) {
+            let d = i * p;
+            let (count, sum) = if d <= r_usize {
+                (large_count[d], large_sum[d])
+            } else {
+                let v = (x / d as u64) as usize;
+                (small_count[v], small_sum[v])
+            };
+            large_count[i] -= count - below_count;
+            large_sum[i] -= p_wide * (sum - below_sum);
+        }
+        for v in (square as usize..=r_usize).rev() {
+            let u = v / p;
+            small_count[v] -= small_count[u] - below_count;
+            small_sum[v] -= p_wide * (small_sum[u] - below_sum);
+        }
+    }
+    (large_count[1], large_sum[1])
+}
+
+/// Meissel-Lehmer
+///
+/// π(x) = φ(x, a) + a - 1 - P2(x, a) where a = π(∛x). Integers surviving the sieve by the primes up to
+/// ∛x have at most two prime factors, and P2 counts the products of two primes above ∛x:
+/// P2(x, a) = Σ (π(x / p_i) - i + 1) over a < i <= π(√x). Each x / p_i is below x^(2/3), so every π lookup
+/// comes from one sieve of that size.
+pub fn meissel_lehmer(x: u64) -> u64 {
+    if x < SIEVE_THRESHOLD {
+        return primes_up_to(x).len() as u64;
+    }
+    let cube_root = x.icbrt();
+    let root = x.isqrt();
+    let primes = primes_up_to(root);
+    let a = primes.partition_point(|p| *p <= cube_root);
+    let b = primes.len();
+
+    // The largest lookup is x / p_(a+1)
+    let table = PrimeTable::new(root, x / primes[a]);
+    let p2: u64 = (a + 1..=b)
+        .map(|i| table.pi(x / primes[i - 1]) + 1 - i as u64)
+        .sum();
+    table.phi(x, a) + a as u64 - 1 - p2
+}
+
+/// π(x) with the fastest available method
+pub fn prime_count(x: u64) -> u64 {
+    if x < SIEVE_THRESHOLD {
+        primes_up_to(x).len() as u64
+    } else {
+        meissel_lehmer(x)
+    }
+}
+
+/// The n-th prime, starting from p_1 = 2
+///
+/// Estimates p_n from the prime number theorem, corrects the estimate with π until it is close, then
+/// sieves forward to the exact prime
+#[cfg(feature = "std")]
+pub fn nth_prime_u64(n: u64) -> Option<u64> {
+    if n == 0 {
+        return None;
+    } else if n < 6 {
+        return Some([2, 3, 5, 7, 11][n as usize - 1]);
+    }
+
+    // p_n < n(ln n + ln ln n) for n >= 6
+    let ln = (n as f64).ln();
+    let upper = n as f64 * (ln + ln.ln());
+    if upper < 1e7 {
+        return primes_up_to(upper as u64).get(n as usize - 1).copied();
+    }
+    if upper > u64::MAX as f64 {
+        // Too large for the estimate to be trusted, u64 runs out of primes around n = 4.2e17 anyway
+        return None;
+    }
+
+    // p_n ~ n(ln n + ln ln n - 1), moved by about ln x per missing prime until it is just below p_n
+    let mut low = (n as f64 * (ln + ln.ln() - 1.0)) as u64;
+    loop {
+        let count = prime_count(low);
+        let gap = (low as f64).ln();
+        if count >= n {
+            let back = ((count - n + 1) as f64 * gap * 1.1) as u64 + 1;
+            low = low.saturating_sub(back);
+        } else if n - count > 10_000 {
+            low += ((n - count) as f64 * gap * 0.9) as u64;
+        } else {
+            // Sieve forward, the remaining primes are roughly (n - count) * ln low away
+            let mut remaining = (n - count) as usize;
+            let span = ((remaining as f64 + 100.0) * gap * 1.5) as u64;
+            let mut start = low + 1;
+            loop {
+                let end = start.checked_add(span)?;
+                let primes = segmented_sieve(start, end);
+                if primes.len() >= remaining {
+                    return Some(primes[remaining - 1]);
+                }
+                remaining -= primes.len();
+                start = end + 1;
+            }
+        }
+    }
+}
+
+/// Prime Counting Trait
+///
+/// π(x), sums of primes and walking through the primes. These only count the positive primes, so π of
+/// anything below 2 is 0 and the prime after a negative integer is 2. π and prime sums are limited to
+/// x <= u64::MAX and panic above it.
+pub trait PrimeCounting<T> {
+    fn prime_pi(self) -> T;
+    fn prime_sum(self) -> Result<T>;
+    #[cfg(feature = "std")]
+    fn nth_prime(self) -> Option<T>;
+    fn next_prime(self) -> Option<T>;
+    fn prev_prime(self) -> Option<T>;
+}
+
+#[macro_export]
+macro_rules! prime_counting {
+    ($t: ident) => {
+        impl<T> PrimeCounting<T> for T
+        where
+            T: $t,
+        {
+            /// Prime Counting Function
+            ///
+            /// The number of primes up to and including self
+            fn prime_pi(self) -> T {
+                if self < T::one() {
+                    return T::zero();
+                }
+                let x = self
+                    .to_u64()
+                    .expect("Prime counting is limited to x <= u64::MAX");
+                // π(x) <= x, so the count always fits
+                T::from_u64(prime_count(x)).unwrap()
+            }
+
+            /// Prime Sum
+            ///
+            /// The sum of the primes up to and including self
+            fn prime_sum(self) -> Result<T> {
+                if self < T::one() {
+                    return Ok(T::zero());
+                }
+                let x = self
+                    .to_u64()
+                    .expect("Prime sums are limited to x <= u64::MAX");
+                let (_, sum) = lucy_hedgehog(x);
+                T::from_u128(sum).ok_or_else(|| {
+                    NumberTheoryErr::Overflow(message!(
+                        "The sum of the primes up to {} overflows {}",
+                        x,
+                        type_name::<T>()
+                    ))
+                })
+            }
+
+            /// n-th Prime
+            ///
+            /// The self-th prime counting from 2 as the first, or None when self is not positive or the
+            /// prime does not fit in T
+            #[cfg(feature = "std")]
+            fn nth_prime(self) -> Option<T> {
+                if self < T::one() {
+                    return None;
+                }
+                T::from_u64(nth_prime_u64(self.to_u64()?)?)
+            }
+
+            /// Next Prime
+            ///
+            /// The smallest prime greater than self, or None if it does not fit in T
+            fn next_prime(self) -> Option<T> {
+                let two = T::one() + T::one();
+                if self < two {
+                    return Some(two);
+                }
+                let mut candidate = self.checked_add(&T::one())?;
+                while !candidate.primality() {
+                    candidate = candidate.checked_add(&T::one())?;
+                }
+                Some(candidate)
+            }
+
+            /// Previous Prime
+            ///
+            /// The largest prime less than self, or None if self is at most 2
+            fn prev_prime(self) -> Option<T> {
+                let two = T::one() + T::one();
+                if self <= two {
+                    return None;
+                }
+                let mut candidate = self - T::one();
+                while !candidate.primality() {
+                    candidate -= T::one();
+                }
+                Some(candidate)
+            }
+        }
+    };
+}
+
+prime_counting!(Integer);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(0, 0)]
+    #[case(1, 0)]
+    #[case(2, 1)]
+    #[case(10, 4)]
+    #[case(100, 25)]
+    #[case(1000, 168)]
+    #[case(65535, 6542)]
+    #[case(65536, 6542)]
+    #[case(100000, 9592)]
+    #[case(1000000, 78498)]
+    #[case(10000000, 664579)]
+    fn u64_prime_count_test(#[case] x: u64, #[case] expected: u64) {
+        assert_eq!(expected, legendre(x));
+        assert_eq!(expected, lucy_hedgehog(x).0);
+        assert_eq!(expected, meissel_lehmer(x));
+    }
+
+    #[rstest]
+    #[case(100000000, 5761455)]
+    #[case(1000000000, 50847534)]
+    fn u64_large_prime_count_test(#[case] x: u64, #[case] expected: u64) {
+        assert_eq!(expected, lucy_hedgehog(x).0);
+        assert_eq!(expected, meissel_lehmer(x));
+    }
+
+    // Sieving up to x^(2/3) takes minutes without optimizations, so these only run in release builds
+    #[rstest]
+    #[case(1000000000000, 37607912018)]
+    #[case(10000000000000, 346065536839)]
+    #[cfg_attr(debug_assertions, ignore)]
+    fn u64_full_width_prime_count_test(#[case] x: u64, #[case] expected: u64) {
+        assert_eq!(expected, meissel_lehmer(x));
+        assert_eq!(expected, prime_count(x));
+    }
+
+    #[test]
+    fn u64_prime_count_range_test() {
+        // Compare every method against the primes themselves, including around the sieve threshold
+        let primes = primes_up_to(70000);
+        for x in (0..2000).chain(65000..70000).step_by(7) {
+            let expected = primes.partition_point(|p| *p <= x) as u64;
+            assert_eq!(expected, legendre(x));
+            assert_eq!(expected, lucy_hedgehog(x).0);
+            assert_eq!(expected, meissel_lehmer(x));
+        }
+    }
+
+    #[rstest]
+    #[case(1, 0)]
+    #[case(2, 2)]
+    #[case(10, 17)]
+    #[case(100, 1060)]
+    #[case(1000, 76127)]
+    #[case(1000000, 37550402023)]
+    #[case(2000000, 142913828922)]
+    #[case(1000000000, 24739512092254535)]
+    fn u64_prime_sum_test(#[case] x: u64, #[case] expected: u128) {
+        assert_eq!(expected, lucy_hedgehog(x).1)
+    }
+
+    #[test]
+    fn segmented_sieve_test() {
+        assert_eq!(vec![2, 3, 5, 7], segmented_sieve(0, 10));
+        assert_eq!(vec![101, 103, 107, 109, 113], segmented_sieve(100, 113));
+        assert!(segmented_sieve(24, 28).is_empty());
+        assert!(segmented_sieve(10, 5).is_empty());
+        let expected: Vec<u64> = primes_up_to(1000000)
+            .into_iter()
+            .filter(|p| *p >= 500000)
+            .collect();
+        assert_eq!(expected, segmented_sieve(500000, 1000000));
+    }
+
+    #[rstest]
+    #[case(1, Some(2))]
+    #[case(5, Some(11))]
+    #[case(10, Some(29))]
+    #[case(1000, Some(7919))]
+    #[case(1000000, Some(15485863))]
+    #[case(10000000, Some(179424673))]
+    #[case(100000000, Some(2038074743))]
+    #[case(0, None)]
+    #[cfg(feature = "std")]
+    fn u64_nth_prime_test(#[case] n: u64, #[case] expected: Option<u64>) {
+        assert_eq!(expected, n.nth_prime())
+    }
+
+    #[rstest]
+    #[case(-5, Some(2))]
+    #[case(0, Some(2))]
+    #[case(2, Some(3))]
+    #[case(100, Some(101))]
+    #[case(101, Some(103))]
+    #[case(1000000, Some(1000003))]
+    fn i64_next_prime_test(#[case] n: i64, #[case] expected: Option<i64>) {
+        assert_eq!(expected, n.next_prime())
+    }
+
+    #[rstest]
+    #[case(-5, None)]
+    #[case(2, None)]
+    #[case(3, Some(2))]
+    #[case(100, Some(97))]
+    #[case(1000003, Some(999983))]
+    fn i64_prev_prime_test(#[case] n: i64, #[case] expected: Option<i64>) {
+        assert_eq!(expected, n.prev_prime())
+    }
+
+    #[test]
+    fn u8_prime_counting_test() {
+        assert_eq!(54, 255u8.prime_pi());
+        assert_eq!(None, 251u8.next_prime());
+        assert_eq!(Some(251), 255u8.prev_prime());
+        assert!(matches!(
+            255u8.prime_sum(),
+            Err(NumberTheoryErr::Overflow(_))
+        ));
+    }
+
+    #[test]
+    fn i32_prime_counting_test() {
+        assert_eq!(0, (-100i32).prime_pi());
+        assert_eq!(168, 1000i32.prime_pi());
+        assert_eq!(76127, 1000i32.prime_sum().unwrap());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn small_types_nth_prime_test() {
+        assert_eq!(Some(251), 54u8.nth_prime());
+        assert_eq!(None, 55u8.nth_prime());
+        assert_eq!(None, (-3i32).nth_prime());
+    }
+}
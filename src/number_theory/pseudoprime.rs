@@ -0,0 +1,275 @@
+//! Pseudoprimes: composites that pass a probable prime test
+//!
+//! Each test below is passed by every prime, so a composite that passes one to base a is a pseudoprime
+//! to that base. The tests get stronger in order: every strong pseudoprime is an Euler pseudoprime, and
+//! every Euler pseudoprime is a Fermat pseudoprime. Carmichael numbers are Fermat pseudoprimes to every
+//! base coprime to them, which is why the Fermat test alone is not used in practice.
+//!
+//! Only integers n > 1 are classified, anything else is neither prime nor a pseudoprime.
+use crate::{
+    general::numbers::Integer,
+    number_theory::{
+        modular_exponentiation::ModularExponentiation, primality::Primality,
+        quadratic_residue::QuadraticResidue,
+    },
+};
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
+
+/// Pseudoprime Trait
+pub trait Pseudoprime<T> {
+    fn is_fermat_pseudoprime(&self, base: T) -> bool;
+    fn is_euler_pseudoprime(&self, base: T) -> bool;
+    fn is_strong_pseudoprime(&self, base: T) -> bool;
+    #[cfg(feature = "alloc")]
+    fn is_carmichael(&self) -> bool;
+}
+
+#[macro_export]
+macro_rules! pseudoprime {
+    ($t: ident) => {
+        impl<T> Pseudoprime<T> for T
+        where
+            T: $t,
+        {
+            /// Fermat Pseudoprime
+            ///
+            /// A composite n with base^(n - 1) = 1 (mod n)
+            fn is_fermat_pseudoprime(&self, base: T) -> bool {
+                let n = *self;
+                let one = T::one();
+                n > one && base.mod_pow(n - one, n).is_ok_and(|r| r == one) && !n.primality()
+            }
+
+            /// Euler Pseudoprime
+            ///
+            /// An odd composite n with base^((n - 1) / 2) = (base / n) (mod n) for the Jacobi symbol, also
+            /// called an Euler-Jacobi pseudoprime. These are the composites that fool Solovay-Strassen.
+            fn is_euler_pseudoprime(&self, base: T) -> bool {
+                let n = *self;
+                let one = T::one();
+                let two = one + one;
+                if n <= two || n % two == T::zero() {
+                    return false;
+                }
+                let expected = match base.jacobi_symbol(n) {
+                    Ok(1) => one,
+                    Ok(-1) => n - one,
+                    _ => return false,
+                };
+                base.mod_pow((n - one) / two, n)
+                    .is_ok_and(|r| r == expected)
+                    && !n.primality()
+            }
+
+            /// Strong Pseudoprime
+            ///
+            /// An odd composite n = d * 2^s + 1, d odd, with base^d = 1 or base^(d * 2^r) = -1 (mod n) for
+            /// some r < s. These are the composites that fool a round of Miller-Rabin.
+            fn is_strong_pseudoprime(&self, base: T) -> bool {
+                let n = *self;
+                let zero = T::zero();
+                let one = T::one();
+                let two = one + one;
+                if n <= two || n % two == zero {
+                    return false;
+                }
+                let mut d = n - one;
+                let mut s = 0;
+                while d % two == zero {
+                    d /= two;
+                    s += 1;
+                }
+                let mut x = match base.mod_pow(d, n) {
+                    Ok(x) => x,
+                    Err(_) => return false,
+                };
+                if x != one && x != n - one {
+                    let mut passed = false;
+                    for _ in 1..s {
+                        x = x.mod_mul(x, n);
+                        if x == n - one {
+                            passed = true;
+                            break;
+                        }
+                    }
+                    if !passed {
+                        return false;
+                    }
+                }
+                !n.primality()
+            }
+
+            /// Carmichael Number
+            ///
+            /// Korselt's criterion: n is a Carmichael number exactly when it is composite, squarefree, and
+            /// p - 1 divides n - 1 for every prime p dividing n
+            #[cfg(feature = "alloc")]
+            fn is_carmichael(&self) -> bool {
+                let n = *self;
+                let one = T::one();
+                if n <= one {
+                    return false;
+                }
+                let factors = n.prime_factorization();
+                factors.len() > 1
+                    && factors.windows(2).all(|pair| pair[0] != pair[1])
+                    && factors.iter().all(|p| (n - one) % (*p - one) == T::zero())
+            }
+        }
+    };
+}
+
+pseudoprime!(Integer);
+
+/// Fermat pseudoprimes to base up to and including limit
+#[cfg(feature = "alloc")]
+pub fn fermat_pseudoprimes<T: Integer>(base: T, limit: T) -> Vec<T> {
+    classify_up_to(limit, |n| n.is_fermat_pseudoprime(base))
+}
+
+/// Euler-Jacobi pseudoprimes to base up to and including limit
+#[cfg(feature = "alloc")]
+pub fn euler_pseudoprimes<T: Integer>(base: T, limit: T) -> Vec<T> {
+    classify_up_to(limit, |n| n.is_euler_pseudoprime(base))
+}
+
+/// Strong pseudoprimes to base up to and including limit
+#[cfg(feature = "alloc")]
+pub fn strong_pseudoprimes<T: Integer>(base: T, limit: T) -> Vec<T> {
+    classify_up_to(limit, |n| n.is_strong_pseudoprime(base))
+}
+
+/// Carmichael numbers up to and including limit
+#[cfg(feature = "alloc")]
+pub fn carmichael_numbers<T: Integer>(limit: T) -> Vec<T> {
+    // Carmichael numbers are odd, and Fermat pseudoprimes to 2, which rules out most n cheaply
+    let two = T::one() + T::one();
+    classify_up_to(limit, |n| {
+        n % two == T::one() && n.is_fermat_pseudoprime(two) && n.is_carmichael()
+    })
+}
+
+/// Every n in [2, limit] matching the classification, counting up without overflowing at T::max_value()
+#[cfg(feature = "alloc")]
+fn classify_up_to<T: Integer>(limit: T, classify: impl Fn(T) -> bool) -> Vec<T> {
+    let mut matches = vec![];
+    let mut n = T::one();
+    while n < limit {
+        n += T::one();
+        if classify(n) {
+            matches.push(n);
+        }
+    }
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::number_theory::gcd::Gcd;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(341, 2, true)]
+    #[case(561, 2, true)]
+    #[case(341, 3, false)]
+    #[case(91, 3, true)]
+    #[case(286, 3, true)]
+    #[case(7, 2, false)]
+    #[case(1, 2, false)]
+    #[case(-341, 2, false)]
+    fn i64_fermat_pseudoprime_test(#[case] n: i64, #[case] base: i64, #[case] expected: bool) {
+        assert_eq!(expected, n.is_fermat_pseudoprime(base))
+    }
+
+    #[rstest]
+    #[case(561, 2, true)]
+    #[case(341, 2, false)]
+    #[case(1905, 2, true)]
+    #[case(2047, 2, true)]
+    #[case(21, 2, false)]
+    #[case(13, 2, false)]
+    fn i64_euler_pseudoprime_test(#[case] n: i64, #[case] base: i64, #[case] expected: bool) {
+        assert_eq!(expected, n.is_euler_pseudoprime(base))
+    }
+
+    #[rstest]
+    #[case(2047, 2, true)]
+    #[case(561, 2, false)]
+    #[case(121, 3, true)]
+    #[case(3215031751, 2, true)]
+    #[case(3215031751, 7, true)]
+    #[case(3215031751, 11, false)]
+    #[case(3825123056546413051, 23, true)]
+    #[case(3825123056546413051, 37, false)]
+    fn i64_strong_pseudoprime_test(#[case] n: i64, #[case] base: i64, #[case] expected: bool) {
+        assert_eq!(expected, n.is_strong_pseudoprime(base))
+    }
+
+    #[test]
+    fn u32_enumeration_test() {
+        assert_eq!(
+            vec![
+                341, 561, 645, 1105, 1387, 1729, 1905, 2047, 2465, 2701, 2821, 3277, 4033, 4369,
+                4371, 4681, 5461, 6601, 7957, 8321, 8481, 8911
+            ],
+            fermat_pseudoprimes(2u32, 10000)
+        );
+        assert_eq!(
+            vec![561, 1105, 1729, 1905, 2047, 2465, 3277, 4033, 4681, 6601, 8321, 8481],
+            euler_pseudoprimes(2u32, 10000)
+        );
+        assert_eq!(
+            vec![2047, 3277, 4033, 4681, 8321],
+            strong_pseudoprimes(2u32, 10000)
+        );
+        assert_eq!(
+            vec![121, 703, 1891, 3281, 8401, 8911],
+            strong_pseudoprimes(3u32, 10000)
+        );
+    }
+
+    #[test]
+    fn carmichael_test() {
+        assert_eq!(
+            vec![
+                561, 1105, 1729, 2465, 2821, 6601, 8911, 10585, 15841, 29341, 41041, 46657, 52633,
+                62745, 63973, 75361
+            ],
+            carmichael_numbers(100000u64)
+        );
+        // Korselt alone, without the Fermat shortcut
+        for n in 0u32..3000 {
+            assert_eq!(
+                [561, 1105, 1729, 2465, 2821].contains(&n),
+                n.is_carmichael()
+            );
+        }
+    }
+
+    #[test]
+    fn pseudoprime_hierarchy_test() {
+        // Strong implies Euler implies Fermat, and Carmichael numbers fool every coprime base
+        for n in 2u32..5000 {
+            for base in 2u32..12 {
+                if n.is_strong_pseudoprime(base) {
+                    assert!(n.is_euler_pseudoprime(base));
+                }
+                if n.is_euler_pseudoprime(base) {
+                    assert!(n.is_fermat_pseudoprime(base));
+                }
+                if n.is_carmichael() && base.gcd(n) == 1 {
+                    assert!(n.is_fermat_pseudoprime(base));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn u8_max_test() {
+        // Counting up to T::max_value() must not overflow
+        assert_eq!(Vec::<u8>::new(), carmichael_numbers(u8::MAX));
+        assert_eq!(vec![91, 121], fermat_pseudoprimes(3u8, u8::MAX));
+    }
+}
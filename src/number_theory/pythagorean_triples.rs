@@ -0,0 +1,654 @@
+//! Pythagorean Triples: generation, the Berggren tree and classification
+use crate::{
+    general::{
+        errors::{message, NumberTheoryErr},
+        numbers::Integer,
+    },
+    number_theory::{gcd::Gcd, parity::Parity, sum_of_squares::prime_powers},
+};
+use alloc::{vec, vec::Vec};
+use core::any::type_name;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+type Result<T> = core::result::Result<T, NumberTheoryErr>;
+
+/// Pythagorean Triple
+///
+/// Positive integers a < b < c with a² + b² = c². The legs are never equal since √2 is irrational, so
+/// listing the shorter leg first makes the representation unique.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PythagoreanTriple<T> {
+    pub a: T,
+    pub b: T,
+    pub c: T,
+}
+
+/// Bound on the triples produced by the generators
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TripleBound<T> {
+    /// c <= bound
+    Hypotenuse(T),
+    /// a + b + c <= bound
+    Perimeter(T),
+}
+
+/// Classification of three integers as a Pythagorean triple
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TripleClass<T> {
+    NotPythagorean,
+    /// gcd(a, b, c) = 1
+    Primitive,
+    /// k times a primitive triple, for k > 1
+    Multiple(T),
+}
+
+/// Primitive Pythagorean triples by Euclid's formula
+///
+/// Lazily yields (m² - n², 2mn, m² + n²) for every m > n > 0 with m - n odd and gcd(m, n) = 1, which
+/// produces each primitive triple exactly once. Triples come in order of m and then n, which is not the
+/// order of their hypotenuses. Both the hypotenuse and the perimeter grow with n for a fixed m, and the
+/// smallest triple for m grows with m, so the iteration stops as soon as n = 1 is out of bounds.
+pub struct PrimitiveTriples<T> {
+    bound: TripleBound<T>,
+    m: T,
+    n: T,
+    done: bool,
+}
+
+/// Primitive Pythagorean triples by the Berggren tree
+///
+/// Every primitive triple descends from (3, 4, 5) through exactly one sequence of the three Berggren
+/// matrices. Children have larger hypotenuses and perimeters than their parent, so the depth first
+/// traversal prunes each subtree at the bound.
+pub struct BerggrenTree<T> {
+    bound: TripleBound<T>,
+    stack: Vec<PythagoreanTriple<T>>,
+}
+
+#[macro_export]
+macro_rules! pythagorean_triple_impls {
+    ($t: ident) => {
+        impl<T> PythagoreanTriple<T>
+        where
+            T: $t,
+        {
+            /// The triple with legs x and y in either order and hypotenuse z, or None when they are
+            /// not positive, x² + y² != z², or the squares are not representable in T
+            pub fn new(x: T, y: T, z: T) -> Option<Self> {
+                let triple = Self::ordered(x, y, z);
+                // z * z hides the sign of the hypotenuse, so check it along with the shorter leg
+                if triple.a <= T::zero() || z <= T::zero() {
+                    return None;
+                }
+                let legs = triple
+                    .a
+                    .checked_mul(&triple.a)?
+                    .checked_add(&triple.b.checked_mul(&triple.b)?)?;
+                (z.checked_mul(&z)? == legs).then_some(triple)
+            }
+
+            /// Euclid's formula (m² - n², 2mn, m² + n²) for m > n > 0, None otherwise or when a
+            /// component overflows
+            pub fn euclid(m: T, n: T) -> Option<Self> {
+                if n <= T::zero() || m <= n {
+                    return None;
+                }
+                let (m2, n2) = (m.checked_mul(&m)?, n * n);
+                let even = m.checked_mul(&n)?.checked_add(&(m * n))?;
+                Some(Self::ordered(m2 - n2, even, m2.checked_add(&n2)?))
+            }
+
+            /// a + b + c, or None when it overflows
+            pub fn perimeter(self) -> Option<T> {
+                self.a.checked_add(&self.b)?.checked_add(&self.c)
+            }
+
+            /// Check if gcd(a, b, c) = 1, which for a triple is the same as gcd(a, b) = 1
+            pub fn is_primitive(self) -> bool {
+                self.a.gcd(self.b) == T::one()
+            }
+
+            /// The primitive triple this triple is a multiple of, along with the multiplier
+            pub fn primitive(self) -> (Self, T) {
+                let k = self.a.gcd(self.b);
+                (Self::ordered(self.a / k, self.b / k, self.c / k), k)
+            }
+
+            /// Berggren Children
+            ///
+            /// The images of the triple under the three Berggren matrices, which are the children of a
+            /// primitive triple in the tree of all primitive triples. Overflows like the integer
+            /// operators when a child is not representable.
+            pub fn children(self) -> [Self; 3] {
+                self.checked_children()
+                    .map(|child| child.expect("Berggren child overflows"))
+            }
+
+            /// Berggren Parent
+            ///
+            /// The parent of a primitive triple in the Berggren tree, through the inverse matrices
+            /// (|a + 2b - 2c|, |2a + b - 2c|, 3c - 2a - 2b). None for the root (3, 4, 5) and for triples
+            /// that are not primitive.
+            pub fn parent(self) -> Option<Self> {
+                let two = T::one() + T::one();
+                let (a, b, c) = (self.a, self.b, self.c);
+                if !self.is_primitive() || c == T::from_u8(5).unwrap() {
+                    return None;
+                }
+                let difference = |x: T, y: T| if x > y { x - y } else { y - x };
+
+                // Rearranged so that no intermediate value exceeds c, 2(c - b) < a and a + b > c
+                let x = difference(a, (c - b) * two);
+                let y = difference(b, (c - a).checked_mul(&two)?);
+                let z = c - (a - (c - b)) * two;
+                Some(Self::ordered(x, y, z))
+            }
+
+            /// The triple with the legs in increasing order
+            fn ordered(x: T, y: T, c: T) -> Self {
+                let (a, b) = if x < y { (x, y) } else { (y, x) };
+                Self { a, b, c }
+            }
+
+            /// The Berggren children, None where a child overflows
+            ///
+            /// A = (a - 2b + 2c, 2a - b + 2c, 2a - 2b + 3c), B = (a + 2b + 2c, 2a + b + 2c, 2a + 2b + 3c)
+            /// and C = (-a + 2b + 2c, -2a + b + 2c, -2a + 2b + 3c). The negative terms are folded into
+            /// c - b and c - a, so no intermediate value exceeds the child, which keeps unsigned types
+            /// and bounds near T::max_value() from overflowing early. Swapping the legs swaps A and C,
+            /// so the order of the legs does not change the set of children.
+            fn checked_children(self) -> [Option<Self>; 3] {
+                let two = T::one() + T::one();
+                let three = two + T::one();
+                let (a, b, c) = (self.a, self.b, self.c);
+                let double = |x: T| x.checked_mul(&two);
+                let child_a = || {
+                    Some(Self::ordered(
+                        a.checked_add(&double(c - b)?)?,
+                        double(a)?.checked_add(&c)?.checked_add(&(c - b))?,
+                        c.checked_add(&double(a.checked_add(&(c - b))?)?)?,
+                    ))
+                };
+                let child_b = || {
+                    let (a2, b2, c2) = (double(a)?, double(b)?, double(c)?);
+                    Some(Self::ordered(
+                        a.checked_add(&b2)?.checked_add(&c2)?,
+                        a2.checked_add(&b)?.checked_add(&c2)?,
+                        a2.checked_add(&b2)?.checked_add(&c.checked_mul(&three)?)?,
+                    ))
+                };
+                let child_c = || {
+                    Some(Self::ordered(
+                        double(b)?.checked_add(&c)?.checked_add(&(c - a))?,
+                        b.checked_add(&double(c - a)?)?,
+                        c.checked_add(&double(b.checked_add(&(c - a))?)?)?,
+                    ))
+                };
+                [child_a(), child_b(), child_c()]
+            }
+        }
+
+        impl<T> TripleBound<T>
+        where
+            T: $t,
+        {
+            /// Check if the triple is within the bound
+            pub fn admits(self, triple: PythagoreanTriple<T>) -> bool {
+                match self {
+                    TripleBound::Hypotenuse(bound) => triple.c <= bound,
+                    TripleBound::Perimeter(bound) => triple
+                        .perimeter()
+                        .is_some_and(|perimeter| perimeter <= bound),
+                }
+            }
+        }
+
+        impl<T> PrimitiveTriples<T>
+        where
+            T: $t,
+        {
+            /// Primitive triples within the bound
+            pub fn new(bound: TripleBound<T>) -> Self {
+                let (one, two) = (T::one(), T::one() + T::one());
+                let done = !PythagoreanTriple::euclid(two, one).is_some_and(|t| bound.admits(t));
+                PrimitiveTriples {
+                    bound,
+                    m: two,
+                    n: T::zero(),
+                    done,
+                }
+            }
+        }
+
+        impl<T> Iterator for PrimitiveTriples<T>
+        where
+            T: $t,
+        {
+            type Item = PythagoreanTriple<T>;
+
+            fn next(&mut self) -> Option<PythagoreanTriple<T>> {
+                let one = T::one();
+                while !self.done {
+                    self.n += one;
+                    if self.n >= self.m {
+                        self.m += one;
+                        self.n = T::zero();
+                        self.done = !PythagoreanTriple::euclid(self.m, one)
+                            .is_some_and(|t| self.bound.admits(t));
+                        continue;
+                    }
+
+                    // Both odd gives an even triple, and a common factor a non-primitive one
+                    if (self.m - self.n).is_even() || self.m.gcd(self.n) != one {
+                        continue;
+                    }
+                    match PythagoreanTriple::euclid(self.m, self.n) {
+                        Some(triple) if self.bound.admits(triple) => return Some(triple),
+                        // Every larger n is out of bounds too, move on to the next m
+                        _ => self.n = self.m - one,
+                    }
+                }
+                None
+            }
+        }
+
+        impl<T> BerggrenTree<T>
+        where
+            T: $t,
+        {
+            /// The Berggren tree pruned at the bound
+            pub fn new(bound: TripleBound<T>) -> Self {
+                let root = PythagoreanTriple::euclid(T::one() + T::one(), T::one());
+                BerggrenTree {
+                    bound,
+                    stack: root
+                        .filter(|&root| bound.admits(root))
+                        .into_iter()
+                        .collect(),
+                }
+            }
+        }
+
+        impl<T> Iterator for BerggrenTree<T>
+        where
+            T: $t,
+        {
+            type Item = PythagoreanTriple<T>;
+
+            fn next(&mut self) -> Option<PythagoreanTriple<T>> {
+                let triple = self.stack.pop()?;
+                for child in triple.checked_children().into_iter().rev().flatten() {
+                    if self.bound.admits(child) {
+                        self.stack.push(child);
+                    }
+                }
+                Some(triple)
+            }
+        }
+    };
+}
+
+pythagorean_triple_impls!(Integer);
+
+#[macro_export]
+macro_rules! pythagorean_triples {
+    ($t: ident) => {
+        /// Primitive Pythagorean triples
+        ///
+        /// Lazily iterate over the primitive triples within the bound by Euclid's formula
+        #[allow(dead_code)]
+        pub fn primitive_triples<T>(bound: TripleBound<T>) -> PrimitiveTriples<T>
+        where
+            T: $t,
+        {
+            PrimitiveTriples::new(bound)
+        }
+
+        /// Pythagorean triples
+        ///
+        /// Lazily iterate over every triple within the bound, each primitive triple followed by its
+        /// multiples
+        #[allow(dead_code)]
+        pub fn pythagorean_triples<T>(
+            bound: TripleBound<T>,
+        ) -> impl Iterator<Item = PythagoreanTriple<T>>
+        where
+            T: $t,
+        {
+            PrimitiveTriples::new(bound).flat_map(move |primitive| {
+                core::iter::successors(Some(primitive), move |triple| {
+                    let next = PythagoreanTriple {
+                        a: triple.a.checked_add(&primitive.a)?,
+                        b: triple.b.checked_add(&primitive.b)?,
+                        c: triple.c.checked_add(&primitive.c)?,
+                    };
+                    bound.admits(next).then_some(next)
+                })
+            })
+        }
+
+        /// Berggren tree
+        ///
+        /// Lazily traverse the primitive triples within the bound depth first from (3, 4, 5)
+        #[allow(dead_code)]
+        pub fn berggren_tree<T>(bound: TripleBound<T>) -> BerggrenTree<T>
+        where
+            T: $t,
+        {
+            BerggrenTree::new(bound)
+        }
+
+        /// Triples with a given leg
+        ///
+        /// Every triple that has leg as one of its legs, in increasing order of hypotenuse. A triple
+        /// (leg, b, c) factors leg² = (c - b)(c + b) into two factors of the same parity, so each
+        /// divisor d < leg of leg² with leg² / d - d even gives b = (leg² / d - d) / 2 and c = d + b.
+        /// Returns an Overflow error when leg² is not representable, and nothing for legs below 3.
+        #[allow(dead_code)]
+        pub fn triples_with_leg<T>(leg: T) -> Result<Vec<PythagoreanTriple<T>>>
+        where
+            T: $t,
+        {
+            let one = T::one();
+            let two = one + one;
+            if leg <= two {
+                return Ok(vec![]);
+            }
+            let square = leg.checked_mul(&leg).ok_or_else(|| {
+                NumberTheoryErr::Overflow(message!(
+                    "The square of the leg overflows {}",
+                    type_name::<T>()
+                ))
+            })?;
+
+            // The divisors of leg² from the prime factorization of leg with doubled exponents
+            let mut divisors = vec![one];
+            for (p, e) in prime_powers(leg) {
+                let mut powers = vec![one];
+                for _ in 0..2 * e {
+                    powers.push(*powers.last().unwrap() * p);
+                }
+                divisors = divisors
+                    .iter()
+                    .flat_map(|&d| powers.iter().map(move |&power| d * power))
+                    .collect();
+            }
+
+            let mut triples: Vec<PythagoreanTriple<T>> = divisors
+                .into_iter()
+                .filter(|&d| d < leg)
+                .filter_map(|d| {
+                    let difference = square / d - d;
+                    if difference.is_odd() {
+                        return None;
+                    }
+                    let b = difference / two;
+                    Some(PythagoreanTriple::ordered(leg, b, d + b))
+                })
+                .collect();
+            triples.sort_unstable_by_key(|triple| triple.c);
+            Ok(triples)
+        }
+
+        /// Classify a triple
+        ///
+        /// Whether x² + y² = z² for positive x, y, z (with the legs in either order), and if so whether
+        /// the triple is primitive or k times a primitive triple
+        #[allow(dead_code)]
+        pub fn classify_triple<T>(x: T, y: T, z: T) -> TripleClass<T>
+        where
+            T: $t,
+        {
+            match PythagoreanTriple::new(x, y, z).map(PythagoreanTriple::primitive) {
+                None => TripleClass::NotPythagorean,
+                Some((_, k)) if k == T::one() => TripleClass::Primitive,
+                Some((_, k)) => TripleClass::Multiple(k),
+            }
+        }
+    };
+}
+
+pythagorean_triples!(Integer);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn t(a: u32, b: u32, c: u32) -> PythagoreanTriple<u32> {
+        PythagoreanTriple { a, b, c }
+    }
+
+    /// Every triple within the bound by direct search, ordered by hypotenuse and then shorter leg
+    fn brute_force(bound: TripleBound<u32>, primitive: bool) -> Vec<PythagoreanTriple<u32>> {
+        let limit = match bound {
+            TripleBound::Hypotenuse(bound) | TripleBound::Perimeter(bound) => bound,
+        };
+        let mut triples = vec![];
+        for c in 1..=limit {
+            for a in 1..c {
+                let b2 = c * c - a * a;
+                let b = b2.isqrt();
+                if a < b && b * b == b2 {
+                    let triple = t(a, b, c);
+                    if bound.admits(triple) && (!primitive || triple.is_primitive()) {
+                        triples.push(triple);
+                    }
+                }
+            }
+        }
+        triples
+    }
+
+    fn sorted<I: Iterator<Item = PythagoreanTriple<u32>>>(
+        triples: I,
+    ) -> Vec<PythagoreanTriple<u32>> {
+        let mut triples: Vec<_> = triples.collect();
+        triples.sort_by_key(|triple| (triple.c, triple.a));
+        triples
+    }
+
+    #[rstest]
+    #[case(3, 4, 5, Some(t(3, 4, 5)))]
+    #[case(12, 5, 13, Some(t(5, 12, 13)))]
+    #[case(3, 4, 6, None)]
+    #[case(0, 5, 5, None)]
+    fn u32_triple_new_test(
+        #[case] x: u32,
+        #[case] y: u32,
+        #[case] z: u32,
+        #[case] expected: Option<PythagoreanTriple<u32>>,
+    ) {
+        assert_eq!(expected, PythagoreanTriple::new(x, y, z))
+    }
+
+    #[rstest]
+    #[case(3, 4, 5, Some(PythagoreanTriple { a: 3, b: 4, c: 5 }))]
+    #[case(-3, 4, 5, None)]
+    #[case(3, -4, 5, None)]
+    #[case(3, 4, -5, None)]
+    fn i64_triple_new_test(
+        #[case] x: i64,
+        #[case] y: i64,
+        #[case] z: i64,
+        #[case] expected: Option<PythagoreanTriple<i64>>,
+    ) {
+        assert_eq!(expected, PythagoreanTriple::new(x, y, z))
+    }
+
+    #[test]
+    fn triple_new_overflow_test() {
+        assert_eq!(
+            Some(PythagoreanTriple { a: 3i8, b: 4, c: 5 }),
+            PythagoreanTriple::new(5, 3, 4).or(PythagoreanTriple::new(4, 3, 5))
+        );
+        assert_eq!(None, PythagoreanTriple::new(60i8, 11, 61));
+        assert_eq!(
+            Some(PythagoreanTriple { a: 8, b: 15, c: 17 }),
+            PythagoreanTriple::new(15u16, 8, 17)
+        );
+    }
+
+    #[rstest]
+    #[case(2, 1, Some(t(3, 4, 5)))]
+    #[case(3, 2, Some(t(5, 12, 13)))]
+    #[case(4, 1, Some(t(8, 15, 17)))]
+    #[case(3, 1, Some(t(6, 8, 10)))]
+    #[case(1, 1, None)]
+    #[case(2, 0, None)]
+    fn u32_euclid_test(
+        #[case] m: u32,
+        #[case] n: u32,
+        #[case] expected: Option<PythagoreanTriple<u32>>,
+    ) {
+        assert_eq!(expected, PythagoreanTriple::euclid(m, n))
+    }
+
+    #[rstest]
+    #[case(3, 4, 5, TripleClass::Primitive)]
+    #[case(20, 21, 29, TripleClass::Primitive)]
+    #[case(30, 16, 34, TripleClass::Multiple(2))]
+    #[case(27, 36, 45, TripleClass::Multiple(9))]
+    #[case(2, 3, 4, TripleClass::NotPythagorean)]
+    #[case(-3, 4, 5, TripleClass::NotPythagorean)]
+    #[case(3, 4, -5, TripleClass::NotPythagorean)]
+    fn i64_classify_triple_test(
+        #[case] x: i64,
+        #[case] y: i64,
+        #[case] z: i64,
+        #[case] expected: TripleClass<i64>,
+    ) {
+        assert_eq!(expected, classify_triple(x, y, z))
+    }
+
+    #[test]
+    fn primitive_test() {
+        assert_eq!((t(3, 4, 5), 7), t(21, 28, 35).primitive());
+        assert!(t(20, 21, 29).is_primitive());
+        assert!(!t(6, 8, 10).is_primitive());
+        assert_eq!(Some(12), t(3, 4, 5).perimeter());
+    }
+
+    #[rstest]
+    #[case(TripleBound::Hypotenuse(100), 16)]
+    #[case(TripleBound::Hypotenuse(1000), 158)]
+    #[case(TripleBound::Perimeter(1000), 70)]
+    #[case(TripleBound::Perimeter(11), 0)]
+    #[case(TripleBound::Hypotenuse(5), 1)]
+    fn primitive_triples_test(#[case] bound: TripleBound<u32>, #[case] count: usize) {
+        let expected = brute_force(bound, true);
+        assert_eq!(count, expected.len());
+        assert_eq!(expected, sorted(primitive_triples(bound)));
+        assert_eq!(expected, sorted(berggren_tree(bound)));
+    }
+
+    #[rstest]
+    #[case(TripleBound::Hypotenuse(100), 52)]
+    #[case(TripleBound::Perimeter(1000), 325)]
+    #[case(TripleBound::Perimeter(12), 1)]
+    fn pythagorean_triples_test(#[case] bound: TripleBound<u32>, #[case] count: usize) {
+        let expected = brute_force(bound, false);
+        assert_eq!(count, expected.len());
+        assert_eq!(expected, sorted(pythagorean_triples(bound)));
+    }
+
+    #[test]
+    fn u8_triples_overflow_test() {
+        // Bounds at the top of the type, where m², the children and the perimeters overflow
+        for bound in [
+            TripleBound::Hypotenuse(u8::MAX),
+            TripleBound::Perimeter(u8::MAX),
+        ] {
+            let widened = match bound {
+                TripleBound::Hypotenuse(h) => TripleBound::Hypotenuse(h as u32),
+                TripleBound::Perimeter(p) => TripleBound::Perimeter(p as u32),
+            };
+            let widen = |triple: PythagoreanTriple<u8>| {
+                t(triple.a as u32, triple.b as u32, triple.c as u32)
+            };
+            assert_eq!(
+                brute_force(widened, true),
+                sorted(primitive_triples(bound).map(widen))
+            );
+            assert_eq!(
+                brute_force(widened, true),
+                sorted(berggren_tree(bound).map(widen))
+            );
+            assert_eq!(
+                brute_force(widened, false),
+                sorted(pythagorean_triples(bound).map(widen))
+            );
+        }
+    }
+
+    #[test]
+    fn berggren_children_test() {
+        assert_eq!(
+            [t(5, 12, 13), t(20, 21, 29), t(8, 15, 17)],
+            t(3, 4, 5).children()
+        );
+        assert_eq!(None, t(3, 4, 5).parent());
+        assert_eq!(None, t(6, 8, 10).parent());
+
+        // Every primitive triple is a child of its parent
+        for triple in primitive_triples(TripleBound::Hypotenuse(2000u32)).skip(1) {
+            let parent = triple.parent().unwrap();
+            assert!(parent.c < triple.c);
+            assert!(parent.children().contains(&triple));
+        }
+    }
+
+    #[rstest]
+    #[case(3, vec![t(3, 4, 5)])]
+    #[case(12, vec![t(5, 12, 13), t(9, 12, 15), t(12, 16, 20), t(12, 35, 37)])]
+    #[case(16, vec![t(12, 16, 20), t(16, 30, 34), t(16, 63, 65)])]
+    #[case(2, vec![])]
+    #[case(0, vec![])]
+    fn u32_triples_with_leg_test(#[case] leg: u32, #[case] expected: Vec<PythagoreanTriple<u32>>) {
+        assert_eq!(expected, triples_with_leg(leg).unwrap())
+    }
+
+    #[test]
+    fn u32_triples_with_leg_range_test() {
+        // The hypotenuse is at most (leg² + 1) / 2
+        for leg in 1..40u32 {
+            let expected: Vec<_> =
+                brute_force(TripleBound::Hypotenuse((leg * leg).div_ceil(2)), false)
+                    .into_iter()
+                    .filter(|triple| triple.a == leg || triple.b == leg)
+                    .collect();
+            assert_eq!(expected, sorted(triples_with_leg(leg).unwrap().into_iter()));
+        }
+    }
+
+    #[test]
+    fn triples_with_leg_overflow_test() {
+        assert!(matches!(
+            triples_with_leg(1i32 << 16),
+            Err(NumberTheoryErr::Overflow(_))
+        ));
+        assert_eq!(
+            vec![PythagoreanTriple {
+                a: 15u8,
+                b: 112,
+                c: 113
+            }],
+            triples_with_leg(15u8)
+                .unwrap()
+                .into_iter()
+                .filter(|triple| triple.c > 100)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn triple_serde_test() {
+        let triple = t(20, 21, 29);
+        let json = serde_json::to_string(&triple).unwrap();
+        assert_eq!(r#"{"a":20,"b":21,"c":29}"#, json);
+        assert_eq!(triple, serde_json::from_str(&json).unwrap());
+    }
+}
@@ -0,0 +1,279 @@
+//! Quadratic Residues: Legendre and Jacobi symbols, modular square roots
+use crate::{
+    general::{
+        errors::{message, NumberTheoryErr},
+        numbers::Integer,
+    },
+    number_theory::{modular_exponentiation::ModularExponentiation, modulus::Modulus},
+};
+use core::any::type_name;
+
+type Result<T> = core::result::Result<T, NumberTheoryErr>;
+
+/// Quadratic Residue Trait
+///
+/// a is a quadratic residue modulo m when x^2 = a (mod m) has a solution. The Legendre symbol answers
+/// this for an odd prime modulus with 1 (residue), -1 (non-residue) or 0 (p divides a). The Jacobi
+/// symbol extends it multiplicatively to odd moduli, where 1 no longer guarantees a square root.
+pub trait QuadraticResidue<T> {
+    fn legendre_symbol(self, p: T) -> Result<i8>;
+    fn jacobi_symbol(self, n: T) -> Result<i8>;
+    fn modular_sqrt(self, p: T) -> Result<T>;
+}
+
+#[macro_export]
+macro_rules! quadratic_residue {
+    ($t: ident) => {
+        impl<T> QuadraticResidue<T> for T
+        where
+            T: $t,
+        {
+            /// Legendre Symbol
+            ///
+            /// (self / p) for an odd prime p by Euler's criterion, self^((p - 1) / 2) = (self / p) (mod p).
+            /// A Domain error is returned when p is not an odd prime, which Euler's criterion detects for
+            /// most composites.
+            fn legendre_symbol(self, p: T) -> Result<i8> {
+                let one = T::one();
+                let two = one + one;
+                if p <= two || p % two == T::zero() {
+                    return Err(NumberTheoryErr::Domain(message!(
+                        "The Legendre symbol of {} requires an odd prime modulus",
+                        type_name::<T>()
+                    )));
+                }
+                match self.mod_pow((p - one) / two, p)? {
+                    r if r == T::zero() => Ok(0),
+                    r if r == one => Ok(1),
+                    r if r == p - one => Ok(-1),
+                    _ => Err(NumberTheoryErr::Domain(message!(
+                        "The Legendre symbol of {} requires a prime modulus, found a composite",
+                        type_name::<T>()
+                    ))),
+                }
+            }
+
+            /// Jacobi Symbol
+            ///
+            /// (self / n) for an odd positive n, computed with quadratic reciprocity without factoring n
+            fn jacobi_symbol(self, n: T) -> Result<i8> {
+                let zero = T::zero();
+                let one = T::one();
+                let two = one + one;
+                if n <= zero || n % two == zero {
+                    return Err(NumberTheoryErr::Domain(message!(
+                        "The Jacobi symbol of {} requires an odd positive modulus",
+                        type_name::<T>()
+                    )));
+                }
+                let three = two + one;
+                let four = two + two;
+                let five = four + one;
+                let eight = four + four;
+
+                let mut a = self.modulus(n);
+                let mut n = n;
+                let mut symbol = 1;
+                while a != zero {
+                    // (2 / n) = -1 exactly when n = 3, 5 (mod 8)
+                    while a % two == zero {
+                        a /= two;
+                        let r = n % eight;
+                        if r == three || r == five {
+                            symbol = -symbol;
+                        }
+                    }
+                    // Reciprocity flips the sign when both are 3 (mod 4)
+                    core::mem::swap(&mut a, &mut n);
+                    if a % four == three && n % four == three {
+                        symbol = -symbol;
+                    }
+                    a %= n;
+                }
+                Ok(if n == one { symbol } else { 0 })
+            }
+
+            /// Modular Square Root
+            ///
+            /// The smaller square root r <= p - r of self modulo a prime p with Tonelli-Shanks. p - r is the
+            /// other root. NoSolution is returned for non-residues, and Domain for moduli that are not
+            /// prime when the algorithm notices.
+            fn modular_sqrt(self, p: T) -> Result<T> {
+                let zero = T::zero();
+                let one = T::one();
+                let two = one + one;
+                if p == two {
+                    return Ok(self.modulus(p));
+                }
+                let a = self.modulus(p);
+                match a.legendre_symbol(p)? {
+                    0 => return Ok(zero),
+                    -1 => {
+                        return Err(NumberTheoryErr::NoSolution(message!(
+                            "Input is not a quadratic residue modulo p in {}",
+                            type_name::<T>()
+                        )))
+                    }
+                    _ => (),
+                }
+
+                // p - 1 = q * 2^s with q odd
+                let mut q = p - one;
+                let mut s = 0;
+                while q % two == zero {
+                    q /= two;
+                    s += 1;
+                }
+                let smaller = |r: T| if r <= p - r { r } else { p - r };
+                if s == 1 {
+                    // p = 3 (mod 4), a^((p + 1) / 4) is a root
+                    return Ok(smaller(a.mod_pow((p + one) / (two + two), p)?));
+                }
+
+                // Any non-residue z generates the 2-Sylow subgroup through z^q
+                let mut z = two;
+                while z.legendre_symbol(p)? != -1 {
+                    z += one;
+                }
+                let mut m = s;
+                let mut c = z.mod_pow(q, p)?;
+                let mut t = a.mod_pow(q, p)?;
+                let mut r = a.mod_pow((q + one) / two, p)?;
+                while t != one {
+                    // Least i with t^(2^i) = 1, which is below m when p is prime
+                    let mut i = 0;
+                    let mut power = t;
+                    while power != one {
+                        power = power.mod_mul(power, p);
+                        i += 1;
+                        if i == m {
+                            return Err(NumberTheoryErr::Domain(message!(
+                                "Modular square roots of {} require a prime modulus",
+                                type_name::<T>()
+                            )));
+                        }
+                    }
+                    let mut b = c;
+                    for _ in 0..m - i - 1 {
+                        b = b.mod_mul(b, p);
+                    }
+                    m = i;
+                    c = b.mod_mul(b, p);
+                    t = t.mod_mul(c, p);
+                    r = r.mod_mul(b, p);
+                }
+                Ok(smaller(r))
+            }
+        }
+    };
+}
+
+quadratic_residue!(Integer);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(0, 7, 0)]
+    #[case(1, 7, 1)]
+    #[case(2, 7, 1)]
+    #[case(3, 7, -1)]
+    #[case(-1, 7, -1)]
+    #[case(-1, 13, 1)]
+    #[case(14, 7, 0)]
+    #[case(1001, 9907, -1)]
+    fn i64_legendre_symbol_test(#[case] a: i64, #[case] p: i64, #[case] expected: i8) {
+        assert_eq!(expected, a.legendre_symbol(p).unwrap())
+    }
+
+    #[rstest]
+    #[case(3, 2)]
+    #[case(3, 1)]
+    #[case(3, 15)]
+    #[case(3, -7)]
+    fn i64_legendre_symbol_domain_test(#[case] a: i64, #[case] p: i64) {
+        assert!(matches!(
+            a.legendre_symbol(p),
+            Err(NumberTheoryErr::Domain(_))
+        ))
+    }
+
+    #[rstest]
+    #[case(1001, 9907, -1)]
+    #[case(19, 45, 1)]
+    #[case(8, 21, -1)]
+    #[case(5, 21, 1)]
+    #[case(30, 1, 1)]
+    #[case(6, 15, 0)]
+    #[case(-2, 15, -1)]
+    fn i64_jacobi_symbol_test(#[case] a: i64, #[case] n: i64, #[case] expected: i8) {
+        assert_eq!(expected, a.jacobi_symbol(n).unwrap())
+    }
+
+    #[test]
+    fn i64_jacobi_symbol_range_test() {
+        // The Jacobi symbol is the product of Legendre symbols over the prime factorization
+        use crate::number_theory::primality::Primality;
+        for n in (3i64..200).step_by(2) {
+            for a in -50i64..50 {
+                let expected: i8 = n
+                    .prime_factorization()
+                    .into_iter()
+                    .map(|p| a.legendre_symbol(p).unwrap())
+                    .product();
+                assert_eq!(expected, a.jacobi_symbol(n).unwrap());
+            }
+        }
+    }
+
+    #[rstest]
+    #[case(0, 2)]
+    #[case(2, 8)]
+    fn i64_jacobi_symbol_domain_test(#[case] a: i64, #[case] n: i64) {
+        assert!(matches!(
+            a.jacobi_symbol(n),
+            Err(NumberTheoryErr::Domain(_))
+        ))
+    }
+
+    #[rstest]
+    #[case(10, 13, 6)]
+    #[case(5, 41, 13)]
+    #[case(2, 7, 3)]
+    #[case(-1, 13, 5)]
+    #[case(0, 13, 0)]
+    #[case(1, 2, 1)]
+    #[case(56, 101, 37)]
+    fn i64_modular_sqrt_test(#[case] a: i64, #[case] p: i64, #[case] expected: i64) {
+        assert_eq!(expected, a.modular_sqrt(p).unwrap())
+    }
+
+    #[test]
+    fn u64_modular_sqrt_range_test() {
+        // p = 2^16 + 1 has p - 1 = 2^16, the worst case for Tonelli-Shanks
+        for p in [3u64, 5, 17, 97, 65537, 998244353, 18446744073709551557] {
+            for a in (1u64..300).chain([p - 1, p / 2]) {
+                match a.modular_sqrt(p) {
+                    Ok(r) => {
+                        assert_eq!(a % p, r.mod_mul(r, p));
+                        assert!(r <= p - r);
+                    }
+                    Err(NumberTheoryErr::NoSolution(_)) => {
+                        assert_eq!(-1, a.legendre_symbol(p).unwrap())
+                    }
+                    Err(error) => panic!("{:?}", error),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn i64_modular_sqrt_no_solution_test() {
+        assert!(matches!(
+            3i64.modular_sqrt(7),
+            Err(NumberTheoryErr::NoSolution(_))
+        ))
+    }
+}
@@ -0,0 +1,561 @@
+//! Self-Initializing Quadratic Sieve (SIQS)
+//!
+//! Factors n by collecting relations y^2 = (product of small primes) (mod n) and combining them over
+//! GF(2) into a congruence of squares X^2 = Y^2 (mod n), where gcd(X - Y, n) is usually a proper factor.
+//!
+//! The values y come from polynomials (Ax + B)^2 - n = A(Ax^2 + 2Bx + C) with A a product of s factor base
+//! primes. The 2^(s - 1) choices of sign in B = ±B_1 ± ... ± B_s share A, so switching polynomials only
+//! shifts the sieve roots by precomputed amounts, which is the self-initialization. Values that are
+//! smooth apart from one prime below a large prime bound are kept and paired up (the large prime
+//! variation). See: https://math.dartmouth.edu/~carlp/PDF/implementation.pdf
+use crate::number_theory::{
+    modular_exponentiation::ModularExponentiation, prime_counting::segmented_sieve,
+    quadratic_residue::QuadraticResidue,
+};
+use num::{BigInt, BigUint, Integer, One, Signed, ToPrimitive, Zero};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::collections::{HashMap, HashSet};
+
+/// Factor base size and sieve half width by the number of digits of n
+const PARAMETERS: [(u32, usize, i64); 11] = [
+    (20, 100, 16384),
+    (25, 150, 16384),
+    (30, 220, 32768),
+    (35, 350, 32768),
+    (40, 550, 65536),
+    (45, 800, 65536),
+    (50, 1200, 65536),
+    (55, 1800, 98304),
+    (60, 2600, 98304),
+    (65, 3600, 131072),
+    (70, 5000, 131072),
+];
+
+/// Relations beyond the factor base size, each extra one roughly halves the chance of failure
+const EXTRA_RELATIONS: usize = 32;
+
+/// Primes below this are too cheap to sieve with, the threshold allows for their contribution instead
+const SMALL_PRIME_CUTOFF: u64 = 30;
+
+/// Large primes are accepted up to this multiple of the largest factor base prime
+const LARGE_PRIME_MULTIPLIER: u64 = 64;
+
+/// y^2 = (-1)^(sign) * (product of factor base primes) * large^2 (mod n)
+///
+/// The factors are column indices with multiplicity, column 0 for -1 and column i + 1 for the i-th
+/// factor base prime
+#[derive(Clone, Debug)]
+struct Relation {
+    y: BigUint,
+    factors: Vec<usize>,
+    large: BigUint,
+}
+
+/// Primes p with (n / p) = 1, with a square root of n modulo each
+struct FactorBase {
+    primes: Vec<u64>,
+    roots: Vec<u64>,
+    logs: Vec<u8>,
+}
+
+impl FactorBase {
+    /// The first size primes that n is a quadratic residue of, or a prime factor of n found on the way
+    fn new(n: &BigUint, size: usize) -> Result<Self, u64> {
+        let mut primes = vec![2];
+        let mut roots = vec![1];
+        let mut bound = 1000;
+        let mut start = 3;
+        while primes.len() < size {
+            for p in segmented_sieve(start, bound) {
+                let residue = (n % p).to_u64().unwrap();
+                match residue.legendre_symbol(p) {
+                    Ok(0) => return Err(p),
+                    Ok(1) => {
+                        primes.push(p);
+                        roots.push(residue.modular_sqrt(p).unwrap());
+                        if primes.len() == size {
+                            break;
+                        }
+                    }
+                    _ => (),
+                }
+            }
+            start = bound + 1;
+            bound *= 2;
+        }
+        let logs = primes
+            .iter()
+            .map(|p| (*p as f64).log2().round() as u8)
+            .collect();
+        Ok(FactorBase {
+            primes,
+            roots,
+            logs,
+        })
+    }
+}
+
+/// Sieve polynomials sharing the same A
+struct PolynomialFamily {
+    a: BigUint,
+    /// Factor base indices of the primes dividing A
+    a_indices: Vec<usize>,
+    /// The B_l, B is the signed sum of these
+    b_terms: Vec<BigUint>,
+    /// A^-1 (mod p) for each factor base prime, 0 for 2 and the primes of A
+    a_inverses: Vec<u64>,
+    /// 2 B_l A^-1 (mod p) for each term and factor base prime, how far the roots move on a sign switch
+    root_shifts: Vec<Vec<u64>>,
+}
+
+impl PolynomialFamily {
+    /// B_l = (A / q_l) * γ where γ = t_l (A / q_l)^-1 (mod q_l), so B^2 = n (mod A) for every choice of signs
+    fn new(n: &BigUint, base: &FactorBase, a_indices: Vec<usize>) -> Self {
+        let a: BigUint = a_indices.iter().map(|i| base.primes[*i]).product();
+        let b_terms: Vec<BigUint> = a_indices
+            .iter()
+            .map(|i| {
+                let q = base.primes[*i];
+                let cofactor = &a / q;
+                let inverse = (&cofactor % q).to_u64().unwrap().mod_pow(q - 2, q).unwrap();
+                let mut gamma = base.roots[*i].mod_mul(inverse, q);
+                if gamma > q / 2 {
+                    gamma = q - gamma;
+                }
+                cofactor * gamma
+            })
+            .collect();
+        debug_assert!({
+            let b: BigUint = b_terms.iter().sum();
+            ((&b * &b) % &a) == (n % &a)
+        });
+
+        let a_inverses: Vec<u64> = base
+            .primes
+            .iter()
+            .enumerate()
+            .map(|(i, p)| {
+                if *p == 2 || a_indices.contains(&i) {
+                    0
+                } else {
+                    (&a % p).to_u64().unwrap().mod_pow(p - 2, *p).unwrap()
+                }
+            })
+            .collect();
+        let root_shifts = b_terms
+            .iter()
+            .map(|b| {
+                base.primes
+                    .iter()
+                    .zip(&a_inverses)
+                    .map(|(p, inverse)| (2 * (b % p).to_u64().unwrap()).mod_mul(*inverse, *p))
+                    .collect()
+            })
+            .collect();
+        PolynomialFamily {
+            a,
+            a_indices,
+            b_terms,
+            a_inverses,
+            root_shifts,
+        }
+    }
+}
+
+/// Everything the sieve needs for one n
+struct Sieve<'a> {
+    n: &'a BigUint,
+    base: FactorBase,
+    half_width: i64,
+    threshold: u8,
+    large_bound: u64,
+}
+
+impl Sieve<'_> {
+    /// Sieve one polynomial, returning full relations and partial relations keyed by their large prime
+    fn sieve(
+        &self,
+        family: &PolynomialFamily,
+        b: &BigInt,
+        roots: &[(u64, u64)],
+        full: &mut Vec<Relation>,
+        partials: &mut HashMap<u64, Relation>,
+    ) {
+        let m = self.half_width;
+        let width = 2 * m as usize;
+        let mut sieve = vec![0u8; width];
+        // Sieve positions i stand for x = i - M, so x = r (mod p) starts at (r + M) mod p
+        let starts: Vec<(usize, usize)> = roots
+            .iter()
+            .zip(&self.base.primes)
+            .map(|((r1, r2), p)| {
+                let shift = m as u64 % p;
+                (((r1 + shift) % p) as usize, ((r2 + shift) % p) as usize)
+            })
+            .collect();
+        for (i, p) in self.base.primes.iter().enumerate() {
+            if *p < SMALL_PRIME_CUTOFF || family.a_inverses[i] == 0 {
+                continue;
+            }
+            let (s1, s2) = starts[i];
+            let log = self.base.logs[i];
+            for start in [s1, s2] {
+                (start..width)
+                    .step_by(*p as usize)
+                    .for_each(|j| sieve[j] = sieve[j].wrapping_add(log));
+            }
+        }
+
+        for (i, _) in sieve
+            .iter()
+            .enumerate()
+            .filter(|(_, v)| **v >= self.threshold)
+        {
+            let x = i as i64 - m;
+            let y = BigInt::from(family.a.clone()) * x + b;
+            let value = (&y * &y - BigInt::from(self.n.clone())) / BigInt::from(family.a.clone());
+            let mut factors = vec![];
+            if value.is_negative() {
+                factors.push(0);
+            }
+            factors.extend(family.a_indices.iter().map(|index| index + 1));
+            let mut value = value.abs().to_biguint().unwrap();
+            if value.is_zero() {
+                continue;
+            }
+            for (index, p) in self.base.primes.iter().enumerate() {
+                let divides = if *p == 2 || family.a_inverses[index] == 0 {
+                    (&value % p).is_zero()
+                } else {
+                    let (s1, s2) = starts[index];
+                    let position = i % *p as usize;
+                    position == s1 || position == s2
+                };
+                if divides {
+                    while (&value % p).is_zero() {
+                        value /= *p;
+                        factors.push(index + 1);
+                    }
+                }
+            }
+
+            let y = y
+                .mod_floor(&BigInt::from(self.n.clone()))
+                .to_biguint()
+                .unwrap();
+            if value.is_one() {
+                full.push(Relation {
+                    y,
+                    factors,
+                    large: BigUint::one(),
+                });
+            } else if let Some(large) = value.to_u64().filter(|v| *v < self.large_bound) {
+                let relation = Relation {
+                    y,
+                    factors,
+                    large: BigUint::one(),
+                };
+                match partials.remove(&large) {
+                    // (y1 y2)^2 = F1 F2 large^2, a full relation once the large prime is squared away
+                    Some(other) if other.y != relation.y => {
+                        let mut factors = other.factors;
+                        factors.extend(relation.factors);
+                        full.push(Relation {
+                            y: other.y * relation.y % self.n,
+                            factors,
+                            large: BigUint::from(large) % self.n,
+                        });
+                    }
+                    _ => {
+                        partials.insert(large, relation);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Pick the primes of A so that A is close to √(2n) / M, which keeps |Ax^2 + 2Bx + C| <= M√(n / 2)
+    fn choose_a(&self, rng: &mut StdRng, used: &mut HashSet<Vec<usize>>) -> Option<Vec<usize>> {
+        let primes = &self.base.primes;
+        let target = ((self.n * 2u32).sqrt() / self.half_width as u64).max(BigUint::from(3u32));
+        let target_bits = target.bits() as f64;
+
+        // A few primes of around 2000, or fewer larger ones when the factor base is small
+        let first = primes.partition_point(|p| *p < SMALL_PRIME_CUTOFF).max(1);
+        let largest = (primes[primes.len() - 1] as f64).log2();
+        let mut s = (target_bits / 11.0).round().max(1.0) as usize;
+        while target_bits / s as f64 > largest && s < primes.len() - first {
+            s += 1;
+        }
+        let size = 2f64.powf(target_bits / s as f64);
+        let mut low = primes
+            .partition_point(|p| (*p as f64) < size / 2.0)
+            .max(first);
+        let mut high = primes.partition_point(|p| (*p as f64) <= size * 2.0);
+        if high <= low + s {
+            low = first;
+            high = primes.len();
+        }
+
+        for _ in 0..100 {
+            let mut indices: Vec<usize> = vec![];
+            let mut product = BigUint::one();
+            while indices.len() + 1 < s {
+                let index = rng.gen_range(low..high);
+                if !indices.contains(&index) {
+                    indices.push(index);
+                    product *= primes[index];
+                }
+            }
+            // The last prime brings the product closest to the target, unless it is the only prime
+            let index = if s == 1 {
+                rng.gen_range(low..high)
+            } else {
+                let remaining = (&target / &product).to_u64().unwrap_or(u64::MAX);
+                (first..primes.len())
+                    .filter(|i| !indices.contains(i))
+                    .min_by_key(|i| primes[*i].abs_diff(remaining))?
+            };
+            indices.push(index);
+            indices.sort();
+            if used.insert(indices.clone()) {
+                return Some(indices);
+            }
+        }
+        None
+    }
+}
+
+/// Dependencies among the rows of a GF(2) matrix by Gaussian elimination, each row tracking which
+/// original rows were added into it
+fn dependencies(rows: &[Vec<usize>], columns: usize) -> Vec<Vec<usize>> {
+    let words = columns.div_ceil(64);
+    let history_words = rows.len().div_ceil(64);
+    let mut matrix: Vec<(Vec<u64>, Vec<u64>)> = rows
+        .iter()
+        .enumerate()
+        .map(|(i, row)| {
+            let mut bits = vec![0u64; words];
+            for column in row {
+                bits[column / 64] ^= 1 << (column % 64);
+            }
+            let mut history = vec![0u64; history_words];
+            history[i / 64] |= 1 << (i % 64);
+            (bits, history)
+        })
+        .collect();
+
+    let mut pivoted = vec![false; matrix.len()];
+    for column in 0..columns {
+        let (word, bit) = (column / 64, 1 << (column % 64));
+        let Some(pivot) = (0..matrix.len()).find(|i| !pivoted[*i] && matrix[*i].0[word] & bit != 0)
+        else {
+            continue;
+        };
+        pivoted[pivot] = true;
+        let (pivot_bits, pivot_history) = matrix[pivot].clone();
+        for (i, (bits, history)) in matrix.iter_mut().enumerate() {
+            if i != pivot && bits[word] & bit != 0 {
+                bits.iter_mut().zip(&pivot_bits).for_each(|(a, b)| *a ^= b);
+                history
+                    .iter_mut()
+                    .zip(&pivot_history)
+                    .for_each(|(a, b)| *a ^= b);
+            }
+        }
+    }
+
+    matrix
+        .into_iter()
+        .filter(|(bits, _)| bits.iter().all(|word| *word == 0))
+        .map(|(_, history)| {
+            (0..rows.len())
+                .filter(|i| history[i / 64] >> (i % 64) & 1 == 1)
+                .collect()
+        })
+        .collect()
+}
+
+/// Self-Initializing Quadratic Sieve
+///
+/// A non-trivial factor of n, or None if every congruence of squares was trivial. n should be an odd
+/// composite that is not a perfect power, as found after trial division, and of at least 15 digits
+/// for the parameters to make sense. Perfect squares and small factors met along the way are still
+/// returned.
+pub fn quadratic_sieve(n: &BigUint) -> Option<BigUint> {
+    if *n < BigUint::from(4u32) {
+        return None;
+    } else if n.is_even() {
+        return Some(BigUint::from(2u32));
+    }
+    let root = n.sqrt();
+    if &root * &root == *n {
+        return Some(root);
+    }
+
+    let digits = (n.bits() as f64 * 2f64.log10()).ceil() as u32;
+    let (_, size, half_width) = *PARAMETERS
+        .iter()
+        .find(|(d, _, _)| digits <= *d)
+        .unwrap_or(&PARAMETERS[PARAMETERS.len() - 1]);
+    let base = match FactorBase::new(n, size) {
+        Ok(base) => base,
+        Err(p) => return Some(BigUint::from(p)),
+    };
+
+    // Sieve values are at most M√(n / 2), allow for one large prime and the unsieved small primes
+    let largest = *base.primes.last().unwrap();
+    let large_bound = largest * LARGE_PRIME_MULTIPLIER;
+    let value_bits = (half_width as f64).log2() + n.bits() as f64 / 2.0 - 0.5;
+    let threshold = (value_bits - (large_bound as f64).log2() - 4.0).max(0.0) as u8;
+    let sieve = Sieve {
+        n,
+        base,
+        half_width,
+        threshold,
+        large_bound,
+    };
+
+    let target = sieve.base.primes.len() + EXTRA_RELATIONS;
+    let mut rng = StdRng::seed_from_u64(0);
+    let mut used = HashSet::new();
+    let mut full: Vec<Relation> = vec![];
+    let mut partials: HashMap<u64, Relation> = HashMap::new();
+    while full.len() < target {
+        let family = PolynomialFamily::new(n, &sieve.base, sieve.choose_a(&mut rng, &mut used)?);
+
+        // Start from B = B_1 + ... + B_s, the roots of (Ax + B)^2 = n are x = A^-1 (±t - B) (mod p)
+        let mut b = BigInt::from(family.b_terms.iter().sum::<BigUint>());
+        let mut roots: Vec<(u64, u64)> = sieve
+            .base
+            .primes
+            .iter()
+            .enumerate()
+            .map(|(i, p)| {
+                let inverse = family.a_inverses[i];
+                let b_mod = b.mod_floor(&BigInt::from(*p)).to_u64().unwrap();
+                let t = sieve.base.roots[i];
+                (
+                    ((t + p - b_mod) % p).mod_mul(inverse, *p),
+                    ((2 * p - t - b_mod) % p).mod_mul(inverse, *p),
+                )
+            })
+            .collect();
+
+        let polynomials = 1usize << (family.b_terms.len() - 1);
+        for i in 0..polynomials {
+            if i > 0 {
+                // Gray code: switch the sign of the one term whose bit changed
+                let v = i.trailing_zeros() as usize;
+                let negative = (i ^ (i >> 1)) >> v & 1 == 1;
+                let term = BigInt::from(family.b_terms[v].clone()) * 2;
+                for ((root, p), shift) in roots
+                    .iter_mut()
+                    .zip(&sieve.base.primes)
+                    .zip(&family.root_shifts[v])
+                {
+                    // Subtracting 2 B_v from B moves the roots up by 2 B_v A^-1 and vice versa
+                    let (up, down) = (shift % p, (p - shift % p) % p);
+                    let delta = if negative { up } else { down };
+                    *root = ((root.0 + delta) % p, (root.1 + delta) % p);
+                }
+                if negative {
+                    b -= term;
+                } else {
+                    b += term;
+                }
+            }
+            sieve.sieve(&family, &b, &roots, &mut full, &mut partials);
+        }
+    }
+
+    // Combine relations into squares
+    let rows: Vec<Vec<usize>> = full.iter().map(|r| r.factors.clone()).collect();
+    let columns = sieve.base.primes.len() + 1;
+    for dependency in dependencies(&rows, columns) {
+        let mut x = BigUint::one();
+        let mut y = BigUint::one();
+        let mut counts = vec![0u32; columns];
+        for index in &dependency {
+            let relation = &full[*index];
+            x = x * &relation.y % n;
+            y = y * &relation.large % n;
+            relation
+                .factors
+                .iter()
+                .for_each(|column| counts[*column] += 1);
+        }
+        for (column, count) in counts.iter().enumerate().skip(1) {
+            let p = BigUint::from(sieve.base.primes[column - 1]);
+            y = y * p.modpow(&BigUint::from(count / 2), n) % n;
+        }
+        let difference = if x >= y { &x - &y } else { &y - &x };
+        let g = difference.gcd(n);
+        if !g.is_one() && g != *n {
+            return Some(g);
+        }
+    }
+    None
+}
+
+/// Self-Initializing Quadratic Sieve for u128
+pub fn quadratic_sieve_u128(n: u128) -> Option<u128> {
+    quadratic_sieve(&BigUint::from(n)).and_then(|factor| factor.to_u128())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[test]
+    fn dependencies_test() {
+        // Rows 0 + 1 + 2 and rows 3 + 4 cancel
+        let rows = vec![vec![0, 1], vec![1, 2], vec![0, 2], vec![3, 3, 4], vec![4]];
+        let mut found = dependencies(&rows, 5);
+        found.sort();
+        assert_eq!(vec![vec![0, 1, 2], vec![3, 4]], found);
+    }
+
+    #[test]
+    fn factor_base_test() {
+        // 2 always, then the odd primes that 1000003 * 1000033 is a residue of
+        let n = BigUint::from(1000003u64 * 1000033);
+        let base = FactorBase::new(&n, 20).unwrap();
+        assert_eq!(20, base.primes.len());
+        for (p, t) in base.primes.iter().zip(&base.roots).skip(1) {
+            assert_eq!((&n % p).to_u64().unwrap(), t.mod_mul(*t, *p));
+        }
+        assert!(matches!(
+            FactorBase::new(&BigUint::from(1000003u64 * 7), 20),
+            Err(7)
+        ));
+    }
+
+    #[rstest]
+    #[case(1000003u128 * 1000033)]
+    #[case(1000000007u128 * 1000000009)]
+    #[case(100000000003u128 * 100000000019)]
+    #[case(1000000000039u128 * 1000000000061)]
+    fn quadratic_sieve_u128_test(#[case] n: u128) {
+        let factor = quadratic_sieve_u128(n).unwrap();
+        assert!(factor > 1 && factor < n && n.is_multiple_of(factor));
+    }
+
+    #[test]
+    fn quadratic_sieve_big_test() {
+        // Two 15 digit primes
+        let p = BigUint::from(100000000000031u64);
+        let q = BigUint::from(100000000000067u64);
+        let factor = quadratic_sieve(&(&p * &q)).unwrap();
+        assert!(factor == p || factor == q);
+    }
+
+    #[test]
+    fn quadratic_sieve_square_test() {
+        assert_eq!(
+            Some(BigUint::from(1000003u64)),
+            quadratic_sieve(&BigUint::from(1000003u64 * 1000003))
+        );
+    }
+}
@@ -0,0 +1,288 @@
+//! Random Generation: primes with structure, coprime pairs and units
+//!
+//! Every function takes the random number generator as a parameter, so a seeded generator such as
+//! StdRng::seed_from_u64 reproduces the same results. Primes are BigUint since the interesting sizes
+//! (RSA and Diffie-Hellman moduli) are far beyond machine integers, and primality is decided by
+//! BigPrimality::is_probable_prime.
+use crate::{
+    general::{
+        errors::{message, NumberTheoryErr},
+        numbers::Integer,
+    },
+    number_theory::{factorization::BigPrimality, gcd::Gcd},
+};
+use num::{BigUint, Integer as _, One};
+use rand::Rng;
+use std::any::type_name;
+
+type Result<T> = std::result::Result<T, NumberTheoryErr>;
+
+/// Strong primes below this many bits leave no room for the large primes in p - 1, p + 1 and r - 1
+const STRONG_PRIME_MIN_BITS: u64 = 64;
+
+/// Uniformly random integer with exactly the given number of bits, so the top bit is always set
+fn random_bits<R: Rng + ?Sized>(rng: &mut R, bits: u64) -> BigUint {
+    let mut bytes = vec![0u8; bits.div_ceil(8) as usize];
+    rng.fill_bytes(&mut bytes);
+    let mut n = BigUint::from_bytes_le(&bytes) % (BigUint::one() << bits);
+    n.set_bit(bits - 1, true);
+    n
+}
+
+/// Random Prime
+///
+/// A uniformly random odd prime with exactly the given number of bits, by drawing random odd candidates
+/// until one is prime
+pub fn random_prime<R: Rng + ?Sized>(rng: &mut R, bits: u64) -> Result<BigUint> {
+    if bits < 2 {
+        return Err(NumberTheoryErr::Domain(message!(
+            "Random primes need at least 2 bits"
+        )));
+    }
+    loop {
+        let mut candidate = random_bits(rng, bits);
+        candidate.set_bit(0, true);
+        if candidate.is_probable_prime() {
+            return Ok(candidate);
+        }
+    }
+}
+
+/// Random Sophie Germain Prime
+///
+/// A random prime q with exactly the given number of bits such that 2q + 1 is also prime
+pub fn random_sophie_germain_prime<R: Rng + ?Sized>(rng: &mut R, bits: u64) -> Result<BigUint> {
+    if bits < 2 {
+        return Err(NumberTheoryErr::Domain(message!(
+            "Sophie Germain primes need at least 2 bits"
+        )));
+    }
+    loop {
+        let q = random_prime(rng, bits)?;
+        let p: BigUint = &q * 2u32 + 1u32;
+        if p.is_probable_prime() {
+            return Ok(q);
+        }
+    }
+}
+
+/// Random Safe Prime
+///
+/// A random prime p = 2q + 1 with exactly the given number of bits, where q is also prime. The
+/// multiplicative group modulo a safe prime has only the subgroups of order 1, 2, q and 2q, which is
+/// what Diffie-Hellman wants.
+pub fn random_safe_prime<R: Rng + ?Sized>(rng: &mut R, bits: u64) -> Result<BigUint> {
+    if bits < 3 {
+        return Err(NumberTheoryErr::Domain(message!(
+            "Safe primes need at least 3 bits"
+        )));
+    }
+    let q = random_sophie_germain_prime(rng, bits - 1)?;
+    Ok(q * 2u32 + 1u32)
+}
+
+/// Random Strong Prime
+///
+/// A random prime p with exactly the given number of bits (at least 64) by Gordon's algorithm, such that
+/// p - 1 has a large prime factor r, p + 1 has a large prime factor s, and r - 1 has a large prime factor
+/// t. This defeats Pollard's p - 1, Williams' p + 1 and cycling attacks on RSA.
+///
+/// 1. Random primes s and t of about half the bits
+/// 2. r is the first prime of the form 2it + 1
+/// 3. p0 = 2 (s^(r - 2) mod r) s - 1, which is 1 (mod r) and -1 (mod s)
+/// 4. p is the first prime of the form p0 + 2jrs with the right number of bits, starting over with new
+///    s and t if there is none
+pub fn random_strong_prime<R: Rng + ?Sized>(rng: &mut R, bits: u64) -> Result<BigUint> {
+    if bits < STRONG_PRIME_MIN_BITS {
+        return Err(NumberTheoryErr::Domain(message!(
+            "Strong primes need at least {} bits",
+            STRONG_PRIME_MIN_BITS
+        )));
+    }
+    let low = BigUint::one() << (bits - 1);
+    let high = BigUint::one() << bits;
+    loop {
+        let s = random_prime(rng, bits / 2 - 8)?;
+        let t = random_prime(rng, bits / 2 - 16)?;
+
+        let step = &t * 2u32;
+        let mut r = &step + 1u32;
+        while !r.is_probable_prime() {
+            r += &step;
+        }
+
+        let p0: BigUint = s.modpow(&(&r - 2u32), &r) * &s * 2u32 - 1u32;
+        let step = &r * &s * 2u32;
+        let mut p = if p0 >= low {
+            p0
+        } else {
+            let j = (&low - &p0).div_ceil(&step);
+            p0 + j * &step
+        };
+        while p < high {
+            if p.is_probable_prime() {
+                return Ok(p);
+            }
+            p += &step;
+        }
+    }
+}
+
+/// Random Coprime Pair
+///
+/// A uniformly random pair (a, b) with 1 <= a, b < bound and gcd(a, b) = 1, by rejection. About
+/// 6 / π^2 ≈ 61% of pairs are coprime, so few draws are needed.
+pub fn random_coprime_pair<T: Integer, R: Rng + ?Sized>(rng: &mut R, bound: T) -> Result<(T, T)> {
+    let one = T::one();
+    if bound <= one + one {
+        return if bound == one + one {
+            Ok((one, one))
+        } else {
+            Err(NumberTheoryErr::Domain(message!(
+                "Random coprime pairs of {} need a bound of at least 2",
+                type_name::<T>()
+            )))
+        };
+    }
+    loop {
+        let a = rng.gen_range(one..bound);
+        let b = rng.gen_range(one..bound);
+        if a.gcd(b) == one {
+            return Ok((a, b));
+        }
+    }
+}
+
+/// Random Unit
+///
+/// A uniformly random unit of Z/nZ, a residue in [0, n) coprime to n, by rejection. Z/1Z is the zero
+/// ring, whose only element 0 is a unit.
+pub fn random_unit<T: Integer, R: Rng + ?Sized>(rng: &mut R, n: T) -> Result<T> {
+    let zero = T::zero();
+    let one = T::one();
+    if n <= zero {
+        return Err(NumberTheoryErr::Domain(message!(
+            "Random units of {} need a positive modulus",
+            type_name::<T>()
+        )));
+    } else if n == one {
+        return Ok(zero);
+    }
+    loop {
+        let a = rng.gen_range(one..n);
+        if a.gcd(n) == one {
+            return Ok(a);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(2)]
+    #[case(3)]
+    #[case(16)]
+    #[case(64)]
+    #[case(256)]
+    fn random_prime_test(#[case] bits: u64) {
+        let mut rng = StdRng::seed_from_u64(bits);
+        for _ in 0..5 {
+            let p = random_prime(&mut rng, bits).unwrap();
+            assert_eq!(bits, p.bits());
+            assert!(p.is_probable_prime());
+        }
+    }
+
+    #[test]
+    fn seeded_reproducibility_test() {
+        let generate = |seed| {
+            let mut rng = StdRng::seed_from_u64(seed);
+            (
+                random_prime(&mut rng, 128).unwrap(),
+                random_safe_prime(&mut rng, 64).unwrap(),
+                random_coprime_pair(&mut rng, 1000u32).unwrap(),
+                random_unit(&mut rng, 1000i64).unwrap(),
+            )
+        };
+        assert_eq!(generate(7), generate(7));
+        assert_ne!(generate(7), generate(8));
+    }
+
+    #[rstest]
+    #[case(3)]
+    #[case(10)]
+    #[case(64)]
+    #[case(128)]
+    fn random_safe_prime_test(#[case] bits: u64) {
+        let mut rng = StdRng::seed_from_u64(bits);
+        let p = random_safe_prime(&mut rng, bits).unwrap();
+        assert_eq!(bits, p.bits());
+        assert!(p.is_probable_prime());
+        assert!(((p - 1u32) / 2u32).is_probable_prime());
+
+        let q = random_sophie_germain_prime(&mut rng, bits).unwrap();
+        assert_eq!(bits, q.bits());
+        assert!(q.is_probable_prime());
+        assert!((q * 2u32 + 1u32).is_probable_prime());
+    }
+
+    #[rstest]
+    #[case(64)]
+    #[case(80)]
+    fn random_strong_prime_test(#[case] bits: u64) {
+        // The largest primes of p - 1, p + 1 and r - 1 have at least bits / 2 - 16 bits
+        let largest = |n: BigUint| n.prime_factorization().pop().unwrap();
+        let mut rng = StdRng::seed_from_u64(bits);
+        let p = random_strong_prime(&mut rng, bits).unwrap();
+        assert_eq!(bits, p.bits());
+        assert!(p.is_probable_prime());
+        let r = largest(&p - 1u32);
+        assert!(r.bits() >= bits / 2 - 16);
+        assert!(largest(&p + 1u32).bits() >= bits / 2 - 8);
+        assert!(largest(r - 1u32).bits() >= bits / 2 - 16);
+    }
+
+    #[test]
+    fn random_strong_prime_domain_test() {
+        let mut rng = StdRng::seed_from_u64(0);
+        assert!(matches!(
+            random_strong_prime(&mut rng, 32),
+            Err(NumberTheoryErr::Domain(_))
+        ));
+        assert!(matches!(
+            random_prime(&mut rng, 1),
+            Err(NumberTheoryErr::Domain(_))
+        ));
+    }
+
+    #[test]
+    fn random_coprime_pair_test() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..1000 {
+            let (a, b) = random_coprime_pair(&mut rng, 100u8).unwrap();
+            assert!((1..100).contains(&a) && (1..100).contains(&b));
+            assert_eq!(1, a.gcd(b));
+        }
+        assert_eq!((1, 1), random_coprime_pair(&mut rng, 2i32).unwrap());
+        assert!(random_coprime_pair(&mut rng, 1i32).is_err());
+    }
+
+    #[test]
+    fn random_unit_test() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut seen = [false; 30];
+        for _ in 0..1000 {
+            let a = random_unit(&mut rng, 30usize).unwrap();
+            assert_eq!(1, a.gcd(30));
+            seen[a] = true;
+        }
+        // Every one of the φ(30) = 8 units appears
+        assert_eq!(8, seen.iter().filter(|s| **s).count());
+        assert_eq!(0, random_unit(&mut rng, 1u64).unwrap());
+        assert!(random_unit(&mut rng, 0i16).is_err());
+    }
+}
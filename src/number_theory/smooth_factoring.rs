@@ -0,0 +1,290 @@
+//! Special-Purpose Factoring: Pollard's p - 1 and Williams' p + 1
+//!
+//! Both methods find a prime p of n when p - 1 (respectively p + 1) is smooth, meaning every prime
+//! factor is at most B1 except possibly one more up to B2. Stage 1 raises to the product of every prime
+//! power up to B1, stage 2 walks the odd numbers in (B1, B2] and accumulates one candidate per prime.
+//! Neither helps when both p - 1 and p + 1 have a large prime factor, which is why RSA primes are
+//! chosen that way (see strong primes).
+use crate::{
+    general::numbers::{abs, Integer},
+    number_theory::{
+        gcd::Gcd, modular_exponentiation::ModularExponentiation, prime_counting::segmented_sieve,
+    },
+};
+
+/// Starting values tried by Williams' p + 1, one of them needs (A^2 - 4 / p) = -1
+const LUCAS_SEEDS: [u8; 6] = [3, 5, 7, 11, 13, 17];
+
+/// Pollard p - 1 bases, another base is tried when a stage 1 or stage 2 gcd finds every prime of n at once
+const POLLARD_BASES: [u8; 3] = [2, 3, 5];
+
+/// Smooth Factoring Trait
+///
+/// A non-trivial factor of |self|, or None when no prime of it has p - 1 (or p + 1) smooth to the
+/// bounds B1 and B2. Stage 2 is skipped when B2 <= B1.
+pub trait SmoothFactoring<T> {
+    fn pollard_p_minus_one(self, b1: u64, b2: u64) -> Option<T>;
+    fn williams_p_plus_one(self, b1: u64, b2: u64) -> Option<T>;
+}
+
+/// Every prime power p^e <= B1, taking the largest power for each prime
+fn stage_one_exponents(b1: u64) -> impl Iterator<Item = u64> {
+    segmented_sieve(2, b1).into_iter().map(move |p| {
+        let mut power = p;
+        while power <= b1 / p {
+            power *= p;
+        }
+        power
+    })
+}
+
+#[macro_export]
+macro_rules! smooth_factoring {
+    ($t: ident) => {
+        impl<T> SmoothFactoring<T> for T
+        where
+            T: $t,
+        {
+            /// Pollard's p - 1
+            ///
+            /// For a base a coprime to n, a^M = 1 (mod p) whenever p - 1 divides M, so gcd(a^M - 1, n)
+            /// picks out the primes whose p - 1 divides M = the product of prime powers up to B1
+            fn pollard_p_minus_one(self, b1: u64, b2: u64) -> Option<T> {
+                let n = abs(self);
+                let one = T::one();
+                let trivial = |g: T| g == one || g == n;
+                if n <= one + one + one {
+                    return None;
+                }
+
+                for base in POLLARD_BASES {
+                    let base = T::from(base)? % n;
+                    let g = base.gcd(n);
+                    if !trivial(g) {
+                        return Some(g);
+                    }
+
+                    // Stage 1: a = base^M
+                    let mut a = base;
+                    for power in stage_one_exponents(b1) {
+                        a = pow(a, power, n);
+                    }
+                    let g = sub(a, one, n).gcd(n);
+                    if g == n {
+                        continue;
+                    } else if g != one {
+                        return Some(g);
+                    }
+
+                    // Stage 2: a^q for each prime q in (B1, B2], stepping through odd q with a^2
+                    if b2 <= b1 {
+                        continue;
+                    }
+                    let square = a.mod_mul(a, n);
+                    let first = (b1.max(2) + 1) | 1;
+                    let mut current = pow(a, first, n);
+                    let mut product = one;
+                    let mut k = first;
+                    for q in segmented_sieve(first, b2) {
+                        while k < q {
+                            current = current.mod_mul(square, n);
+                            k += 2;
+                        }
+                        product = product.mod_mul(sub(current, one, n), n);
+                    }
+                    let g = product.gcd(n);
+                    if !trivial(g) {
+                        return Some(g);
+                    }
+                }
+                None
+            }
+
+            /// Williams' p + 1
+            ///
+            /// Works in the group of norm 1 elements of F_{p^2}, which has order p + 1 when the
+            /// discriminant A^2 - 4 is a non-residue modulo p. The Lucas sequence V_k(A) plays the part
+            /// of a^k, with V_M(A) = 2 (mod p) whenever p + 1 divides M. Several seeds A are tried since
+            /// the discriminant condition cannot be checked without knowing p.
+            fn williams_p_plus_one(self, b1: u64, b2: u64) -> Option<T> {
+                let n = abs(self);
+                let one = T::one();
+                let two = one + one;
+                let trivial = |g: T| g == one || g == n;
+                if n <= two + one {
+                    return None;
+                }
+
+                for seed in LUCAS_SEEDS {
+                    let seed = T::from(seed)? % n;
+
+                    // Stage 1: V_M(A) by composing V_mn(A) = V_m(V_n(A))
+                    let mut v = seed;
+                    for power in stage_one_exponents(b1) {
+                        v = lucas(v, power, n);
+                    }
+                    let g = sub(v, two, n).gcd(n);
+                    if g == n {
+                        continue;
+                    } else if g != one {
+                        return Some(g);
+                    }
+                    if b2 <= b1 {
+                        continue;
+                    }
+
+                    // Stage 2: V_q(V_M) for each prime q in (B1, B2], stepping through odd k with
+                    // V_(k+2) = V_k V_2 - V_(k-2)
+                    let first = (b1.max(2) + 1) | 1;
+                    let v2 = lucas(v, 2, n);
+                    let mut previous = lucas(v, first - 2, n);
+                    let mut current = lucas(v, first, n);
+                    let mut product = one;
+                    let mut k = first;
+                    for q in segmented_sieve(first, b2) {
+                        while k < q {
+                            let next = sub(current.mod_mul(v2, n), previous, n);
+                            previous = current;
+                            current = next;
+                            k += 2;
+                        }
+                        product = product.mod_mul(sub(current, two, n), n);
+                    }
+                    let g = product.gcd(n);
+                    if !trivial(g) {
+                        return Some(g);
+                    }
+                }
+                None
+            }
+        }
+
+        /// a - b (mod n) for a, b in [0, n)
+        fn sub<T: $t>(a: T, b: T, n: T) -> T {
+            if a >= b {
+                a - b
+            } else {
+                a + (n - b)
+            }
+        }
+
+        /// a^e (mod n) for an exponent that may not fit in T
+        fn pow<T: $t>(a: T, e: u64, n: T) -> T {
+            let mut result = T::one() % n;
+            let mut base = a;
+            let mut e = e;
+            while e > 0 {
+                if e & 1 == 1 {
+                    result = result.mod_mul(base, n);
+                }
+                base = base.mod_mul(base, n);
+                e >>= 1;
+            }
+            result
+        }
+
+        /// The Lucas sequence V_k(a) (mod n), with V_0 = 2, V_1 = a and V_(k+1) = a V_k - V_(k-1)
+        ///
+        /// Uses the ladder V_2k = V_k^2 - 2 and V_(2k+1) = V_k V_(k+1) - a, for k >= 1
+        fn lucas<T: $t>(a: T, k: u64, n: T) -> T {
+            let two = T::one() + T::one();
+            let (mut low, mut high) = (a, sub(a.mod_mul(a, n), two, n));
+            for bit in (0..63 - k.leading_zeros()).rev() {
+                if k >> bit & 1 == 1 {
+                    low = sub(low.mod_mul(high, n), a, n);
+                    high = sub(high.mod_mul(high, n), two, n);
+                } else {
+                    high = sub(low.mod_mul(high, n), a, n);
+                    low = sub(low.mod_mul(low, n), two, n);
+                }
+            }
+            low
+        }
+    };
+}
+
+smooth_factoring!(Integer);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    // 1000000009 - 1 = 2^3 3^2 7 109^2 167 and 1000000007 + 1 is the same number
+    // 1000000033 - 1 = 2^5 3 127 82021 and 1000000349 + 1 = 2 3^3 5^2 17 43573
+    // 1000000000169 has large primes in both p - 1 and p + 1
+    const HARD: u128 = 1000000000169;
+
+    #[test]
+    fn lucas_test() {
+        // V_k(3) = 2, 3, 7, 18, 47, 123, 322, 843, ... (mod 1000)
+        let expected = [3u64, 7, 18, 47, 123, 322, 843, 207, 778, 127];
+        for (k, v) in expected.iter().enumerate() {
+            assert_eq!(*v, lucas(3u64, k as u64 + 1, 1000));
+        }
+    }
+
+    #[rstest]
+    #[case(1000000009, 20000, 0)]
+    #[case(1000000033, 1000, 100000)]
+    fn u128_pollard_p_minus_one_test(#[case] p: u128, #[case] b1: u64, #[case] b2: u64) {
+        assert_eq!(Some(p), (p * HARD).pollard_p_minus_one(b1, b2));
+        assert_eq!(
+            Some(p as i128),
+            (-(p as i128) * HARD as i128).pollard_p_minus_one(b1, b2)
+        );
+    }
+
+    #[test]
+    fn pollard_p_minus_one_stage_two_test() {
+        // 82021 is out of reach of stage 1 alone
+        assert_eq!(None, (1000000033 * HARD).pollard_p_minus_one(1000, 0));
+    }
+
+    #[test]
+    fn pollard_p_minus_one_next_base_test() {
+        // With B1 = 20 and B2 = 200 the order of 2 is caught by stage 2 modulo both 1013 and 1697, so
+        // base 2 finds all of n. The order of 3 modulo 1697 is out of reach, and base 3 splits n.
+        assert_eq!(Some(1013), (1013u64 * 1697).pollard_p_minus_one(20, 200));
+    }
+
+    #[rstest]
+    #[case(1000000007, 20000, 0)]
+    #[case(1000000349, 1000, 100000)]
+    fn u128_williams_p_plus_one_test(#[case] p: u128, #[case] b1: u64, #[case] b2: u64) {
+        assert_eq!(Some(p), (p * HARD).williams_p_plus_one(b1, b2));
+        assert_eq!(None, (p * HARD).pollard_p_minus_one(b1, b2));
+    }
+
+    #[test]
+    fn safe_prime_test() {
+        // Safe primes p = 2q + 1 have a large prime in p - 1, and these also have one in p + 1
+        let n = 200001587u64 * 200003303;
+        assert_eq!(None, n.pollard_p_minus_one(10000, 1000000));
+        assert_eq!(None, n.williams_p_plus_one(10000, 1000000));
+    }
+
+    #[test]
+    fn small_input_test() {
+        assert_eq!(None, 3u8.pollard_p_minus_one(100, 1000));
+        assert_eq!(None, 1i32.williams_p_plus_one(100, 1000));
+        assert_eq!(Some(2), 12u8.pollard_p_minus_one(100, 1000));
+    }
+
+    #[test]
+    fn u8_range_test() {
+        for n in 4u8..=255 {
+            for factor in [
+                n.pollard_p_minus_one(0, 10),
+                n.williams_p_plus_one(0, 10),
+                n.pollard_p_minus_one(10, 100),
+                n.williams_p_plus_one(10, 100),
+            ]
+            .into_iter()
+            .flatten()
+            {
+                assert!(factor > 1 && factor < n && n % factor == 0);
+            }
+        }
+    }
+}
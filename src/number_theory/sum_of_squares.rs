@@ -0,0 +1,447 @@
+//! Sums of Squares: representations by two, three and four squares
+use crate::{
+    general::{
+        errors::{message, NumberTheoryErr},
+        numbers::Integer,
+    },
+    number_theory::{gaussian_int::cornacchia, integer_roots::IntegerRoots, primality::Primality},
+};
+use alloc::{vec, vec::Vec};
+use core::any::type_name;
+use rand::Rng;
+
+type Result<T> = core::result::Result<T, NumberTheoryErr>;
+
+/// Sum of Squares Trait
+///
+/// Fermat and Euler: n is a sum of two squares exactly when every prime p = 3 (mod 4) appears in n to
+/// an even power. Legendre: n is a sum of three squares unless n = 4^a(8b + 7). Lagrange: every
+/// non-negative n is a sum of four squares.
+///
+/// Representations are of non-negative integers by non-negative squares. The three and four square
+/// representations are randomized (Rabin-Shallit) and take the Rng as a parameter, so a seeded generator
+/// reproduces the same representation.
+pub trait SumOfSquares<T> {
+    fn is_sum_of_two_squares(&self) -> bool;
+    fn two_squares(self) -> Vec<(T, T)>;
+    fn r2(self) -> usize;
+    fn is_sum_of_three_squares(&self) -> bool;
+    fn three_squares<R: Rng + ?Sized>(self, rng: &mut R) -> Result<(T, T, T)>;
+    fn four_squares<R: Rng + ?Sized>(self, rng: &mut R) -> Result<(T, T, T, T)>;
+}
+
+/// The prime factorization of n > 0 as (p, exponent) pairs
+pub(crate) fn prime_powers<T: Integer>(n: T) -> Vec<(T, u32)> {
+    let mut powers: Vec<(T, u32)> = vec![];
+    for p in n.prime_factorization() {
+        match powers.last_mut() {
+            Some((q, e)) if *q == p => *e += 1,
+            _ => powers.push((p, 1)),
+        }
+    }
+    powers
+}
+
+/// n = 4^k·m with m not divisible by 4, returned as (m, 2^k)
+fn remove_fours<T: Integer>(n: T) -> (T, T) {
+    let two = T::one() + T::one();
+    let four = two + two;
+    let (mut m, mut scale) = (n, T::one());
+    while m != T::zero() && m % four == T::zero() {
+        m /= four;
+        scale *= two;
+    }
+    (m, scale)
+}
+
+/// A quick attempt at r = a² + b² without factoring r: squares, and Cornacchia's algorithm on r or r / 2
+/// when it is 1 (mod 4). Cornacchia's answer satisfies the equation by construction, so it is correct
+/// even when r turns out to be composite, in which case it usually fails instead.
+fn two_squares_quick<T: Integer>(r: T) -> Option<(T, T)> {
+    let one = T::one();
+    let two = one + one;
+    let four = two + two;
+    if r.is_perfect_square() {
+        return Some((r.isqrt(), T::zero()));
+    }
+    if r % four == one {
+        return cornacchia(one, r).ok();
+    }
+
+    // 2(c² + d²) = (c + d)² + (c - d)²
+    if r % four == two {
+        let half = r / two;
+        let (c, d) = if half.is_perfect_square() {
+            (half.isqrt(), T::zero())
+        } else if half % four == one {
+            cornacchia(one, half).ok()?
+        } else {
+            return None;
+        };
+        return Some((c + d, if c > d { c - d } else { d - c }));
+    }
+    None
+}
+
+/// The components in decreasing order, each multiplied by scale
+fn sorted_scaled<T: Integer, const N: usize>(mut squares: [T; N], scale: T) -> [T; N] {
+    squares.sort_unstable_by(|a, b| b.cmp(a));
+    squares.map(|a| a * scale)
+}
+
+fn negative_error<T>() -> NumberTheoryErr {
+    NumberTheoryErr::Domain(message!(
+        "Negative {} are not sums of squares",
+        type_name::<T>()
+    ))
+}
+
+#[macro_export]
+macro_rules! sum_of_squares {
+    ($t: ident) => {
+        impl<T> SumOfSquares<T> for T
+        where
+            T: $t,
+        {
+            /// Sum of Two Squares
+            ///
+            /// Decided from the prime factorization: every prime p = 3 (mod 4) has an even exponent
+            fn is_sum_of_two_squares(&self) -> bool {
+                let four = T::from_u8(4).unwrap();
+                let three = T::from_u8(3).unwrap();
+                if *self <= T::zero() {
+                    return *self == T::zero();
+                }
+                prime_powers(*self)
+                    .into_iter()
+                    .all(|(p, e)| p % four != three || e % 2 == 0)
+            }
+
+            /// Two Squares Representations
+            ///
+            /// Every representation self = a² + b² with 0 <= a <= b, in increasing order of a. Each
+            /// prime p = 1 (mod 4) is written as c² + d² by Cornacchia's algorithm, and the
+            /// representations are built up prime by prime with the Brahmagupta-Fibonacci identity
+            /// (a² + b²)(c² + d²) = (ac - bd)² + (ad + bc)² = (ac + bd)² + (ad - bc)², which corresponds
+            /// to multiplying by c + di or c - di in the Gaussian integers. Empty when self is not a sum
+            /// of two squares.
+            fn two_squares(self) -> Vec<(T, T)> {
+                let zero = T::zero();
+                let one = T::one();
+                let two = one + one;
+                let four = two + two;
+                if self <= zero {
+                    return if self == zero {
+                        vec![(zero, zero)]
+                    } else {
+                        vec![]
+                    };
+                }
+                let difference = |x: T, y: T| if x > y { x - y } else { y - x };
+                let unordered = |(x, y): (T, T)| if x > y { (y, x) } else { (x, y) };
+
+                let mut representations = vec![(zero, one)];
+                for (p, e) in prime_powers(self) {
+                    if p % four == two + one {
+                        if e % 2 == 1 {
+                            return vec![];
+                        }
+                        let scale = p.pow(e / 2);
+                        for (a, b) in representations.iter_mut() {
+                            *a *= scale;
+                            *b *= scale;
+                        }
+                        continue;
+                    }
+                    let (c, d) = if p == two {
+                        (one, one)
+                    } else {
+                        cornacchia(one, p).expect("Primes 1 (mod 4) are sums of two squares")
+                    };
+                    for _ in 0..e {
+                        representations = representations
+                            .into_iter()
+                            .flat_map(|(a, b)| {
+                                [
+                                    (difference(a * c, b * d), a * d + b * c),
+                                    (a * c + b * d, difference(a * d, b * c)),
+                                ]
+                            })
+                            .map(unordered)
+                            .collect();
+                        representations.sort_unstable();
+                        representations.dedup();
+                    }
+                }
+                representations
+            }
+
+            /// Sum of Two Squares Function
+            ///
+            /// r₂(n), the number of ordered pairs of integers (x, y), signs included, with
+            /// x² + y² = n. Jacobi's two square theorem gives r₂(n) = 4(d₁(n) - d₃(n)), where dᵢ counts
+            /// the divisors congruent to i modulo 4, which is 4∏(e + 1) over the primes p = 1 (mod 4)
+            /// when n is a sum of two squares and 0 otherwise. r₂(0) = 1.
+            fn r2(self) -> usize {
+                let four = T::from_u8(4).unwrap();
+                if self <= T::zero() {
+                    return usize::from(self == T::zero());
+                }
+                let mut count = 4;
+                for (p, e) in prime_powers(self) {
+                    match (p % four).to_u8().unwrap() {
+                        1 => count *= e as usize + 1,
+                        3 if e % 2 == 1 => return 0,
+                        _ => (),
+                    }
+                }
+                count
+            }
+
+            /// Sum of Three Squares
+            ///
+            /// Legendre's three square theorem: true unless self = 4^a(8b + 7)
+            fn is_sum_of_three_squares(&self) -> bool {
+                if *self < T::zero() {
+                    return false;
+                }
+                let (m, _) = remove_fours(*self);
+                m % T::from_u8(8).unwrap() != T::from_u8(7).unwrap()
+            }
+
+            /// Three Squares Representation
+            ///
+            /// self = a² + b² + c² with a >= b >= c >= 0. Factors of 4 are removed and put back as
+            /// factors of 2 in every component, then a random a is drawn until the rest m - a² can be
+            /// written as a sum of two squares without factoring it. NoSolution is returned when
+            /// Legendre's condition rules a representation out, and a Domain error for negative inputs.
+            fn three_squares<R: Rng + ?Sized>(self, rng: &mut R) -> Result<(T, T, T)> {
+                let zero = T::zero();
+                if self < zero {
+                    return Err(negative_error::<T>());
+                }
+                if !self.is_sum_of_three_squares() {
+                    return Err(NumberTheoryErr::NoSolution(message!(
+                        "Integers of the form 4^a(8b + 7) are not sums of three squares in {}",
+                        type_name::<T>()
+                    )));
+                }
+                let (m, scale) = remove_fours(self);
+                let limit = m.isqrt();
+                loop {
+                    let a = rng.gen_range(zero..=limit);
+                    if let Some((b, c)) = two_squares_quick(m - a * a) {
+                        let [a, b, c] = sorted_scaled([a, b, c], scale);
+                        return Ok((a, b, c));
+                    }
+                }
+            }
+
+            /// Four Squares Representation
+            ///
+            /// self = a² + b² + c² + d² with a >= b >= c >= d >= 0 by the Rabin-Shallit algorithm:
+            /// after removing factors of 4, random a and b are drawn until m - a² - b² is a sum of two
+            /// squares found without factoring, typically a prime 1 (mod 4) split by Cornacchia's
+            /// algorithm. Returns a Domain error for negative inputs.
+            fn four_squares<R: Rng + ?Sized>(self, rng: &mut R) -> Result<(T, T, T, T)> {
+                let zero = T::zero();
+                if self < zero {
+                    return Err(negative_error::<T>());
+                }
+                let (m, scale) = remove_fours(self);
+                loop {
+                    let a = rng.gen_range(zero..=m.isqrt());
+                    let rest = m - a * a;
+                    let b = rng.gen_range(zero..=rest.isqrt());
+                    if let Some((c, d)) = two_squares_quick(rest - b * b) {
+                        let [a, b, c, d] = sorted_scaled([a, b, c, d], scale);
+                        return Ok((a, b, c, d));
+                    }
+                }
+            }
+        }
+    };
+}
+
+sum_of_squares!(Integer);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "std")]
+    use rand::{rngs::StdRng, SeedableRng};
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(0, true)]
+    #[case(1, true)]
+    #[case(2, true)]
+    #[case(3, false)]
+    #[case(9, true)]
+    #[case(21, false)]
+    #[case(45, true)]
+    #[case(-5, false)]
+    fn i64_is_sum_of_two_squares_test(#[case] n: i64, #[case] expected: bool) {
+        assert_eq!(expected, n.is_sum_of_two_squares())
+    }
+
+    #[rstest]
+    #[case(0, vec![(0, 0)])]
+    #[case(1, vec![(0, 1)])]
+    #[case(2, vec![(1, 1)])]
+    #[case(3, vec![])]
+    #[case(25, vec![(0, 5), (3, 4)])]
+    #[case(50, vec![(1, 7), (5, 5)])]
+    #[case(325, vec![(1, 18), (6, 17), (10, 15)])]
+    #[case(441, vec![(0, 21)])]
+    #[case(-2, vec![])]
+    fn i64_two_squares_test(#[case] n: i64, #[case] expected: Vec<(i64, i64)>) {
+        assert_eq!(expected, n.two_squares())
+    }
+
+    #[test]
+    fn u32_two_squares_range_test() {
+        // Against a direct search over a, for an unsigned type
+        for n in 0..3000u32 {
+            let expected: Vec<(u32, u32)> = (0..=(n / 2).isqrt())
+                .filter(|a| (n - a * a).is_perfect_square())
+                .map(|a| (a, (n - a * a).isqrt()))
+                .collect();
+            assert_eq!(expected, n.two_squares());
+            assert_eq!(!expected.is_empty(), n.is_sum_of_two_squares());
+        }
+    }
+
+    #[test]
+    fn u8_two_squares_max_test() {
+        assert_eq!(vec![(7, 15)], 274u16.two_squares());
+        assert_eq!(vec![(5, 15), (9, 13)], 250u8.two_squares());
+    }
+
+    #[rstest]
+    #[case(0, 1)]
+    #[case(1, 4)]
+    #[case(2, 4)]
+    #[case(3, 0)]
+    #[case(5, 8)]
+    #[case(25, 12)]
+    #[case(325, 24)]
+    #[case(-1, 0)]
+    #[case(1000000000061, 8)]
+    #[case(1000000000039, 0)]
+    #[case(5 * 1000000000061, 16)]
+    fn i64_r2_test(#[case] n: i64, #[case] expected: usize) {
+        assert_eq!(expected, n.r2())
+    }
+
+    #[test]
+    fn i64_two_squares_full_width_test() {
+        // Primes around 10^12 and i64::MAX = 7² · 73 · 127 · 337 · 92737 · 649657
+        let n = 1000000000061i64;
+        let representations = n.two_squares();
+        assert_eq!(1, representations.len());
+        let (a, b) = representations[0];
+        assert_eq!(n, a * a + b * b);
+        assert!(i64::MAX.two_squares().is_empty());
+        assert!(!i64::MAX.is_sum_of_two_squares());
+        assert_eq!(0, i64::MAX.r2());
+    }
+
+    #[test]
+    fn i64_r2_range_test() {
+        // Count the ordered pairs with signs from the representations
+        for n in 1..2000i64 {
+            let count: usize = n
+                .two_squares()
+                .into_iter()
+                .map(|(a, b)| {
+                    let signs = if a == 0 { 2 } else { 4 };
+                    if a == b {
+                        signs
+                    } else {
+                        2 * signs
+                    }
+                })
+                .sum();
+            assert_eq!(count, n.r2());
+        }
+    }
+
+    #[rstest]
+    #[case(0, true)]
+    #[case(6, true)]
+    #[case(7, false)]
+    #[case(28, false)]
+    #[case(112, false)]
+    #[case(60, false)]
+    #[case(30, true)]
+    #[case(-3, false)]
+    fn i64_is_sum_of_three_squares_test(#[case] n: i64, #[case] expected: bool) {
+        assert_eq!(expected, n.is_sum_of_three_squares())
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn u64_three_squares_range_test() {
+        let mut rng = StdRng::seed_from_u64(3);
+        for n in 0..20000u64 {
+            match n.three_squares(&mut rng) {
+                Ok((a, b, c)) => {
+                    assert_eq!(n, a * a + b * b + c * c);
+                    assert!(a >= b && b >= c);
+                }
+                Err(NumberTheoryErr::NoSolution(_)) => assert!(!n.is_sum_of_three_squares()),
+                Err(err) => panic!("{:?}", err),
+            }
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn u64_four_squares_range_test() {
+        let mut rng = StdRng::seed_from_u64(4);
+        for n in 0..20000u64 {
+            let (a, b, c, d) = n.four_squares(&mut rng).unwrap();
+            assert_eq!(n, a * a + b * b + c * c + d * d);
+            assert!(a >= b && b >= c && c >= d);
+        }
+    }
+
+    #[rstest]
+    #[case(u64::MAX)]
+    #[case(1 << 62)]
+    #[case(999999999999999989)]
+    #[cfg(feature = "std")]
+    fn u64_large_squares_test(#[case] n: u64) {
+        let mut rng = StdRng::seed_from_u64(5);
+        let (a, b, c, d) = n.four_squares(&mut rng).unwrap();
+        assert_eq!(
+            n as u128,
+            [a, b, c, d].iter().map(|&x| x as u128 * x as u128).sum()
+        );
+        if let Ok((a, b, c)) = n.three_squares(&mut rng) {
+            assert_eq!(
+                n as u128,
+                [a, b, c].iter().map(|&x| x as u128 * x as u128).sum()
+            );
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn i64_squares_domain_test() {
+        let mut rng = StdRng::seed_from_u64(6);
+        assert!(matches!(
+            (-1i64).four_squares(&mut rng),
+            Err(NumberTheoryErr::Domain(_))
+        ));
+        assert!(matches!(
+            (-1i64).three_squares(&mut rng),
+            Err(NumberTheoryErr::Domain(_))
+        ));
+        assert!(matches!(
+            7i64.three_squares(&mut rng),
+            Err(NumberTheoryErr::NoSolution(_))
+        ));
+    }
+}
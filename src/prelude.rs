@@ -1,11 +1,67 @@
 pub use crate::number_theory::{
-    continued_fraction::ContinuedFraction,
+    const_fns::{
+        egcd_i32, egcd_i64, euler_totient_u32, euler_totient_u64, first_primes, gcd_u32, gcd_u64,
+        inverse_table, is_prime_trial_u32, is_prime_trial_u64, is_prime_u32, is_prime_u64,
+        mod_inverse_u32, mod_inverse_u64, mod_mul_u32, mod_mul_u64, mod_pow_u32, mod_pow_u64,
+        prime_sieve, totient_table,
+    },
     euclidean_algs::{Euclidean, ExtendedEuclidean},
     euler_totient::EulerTotient,
     factorial::Factorial,
-    gcd::Gcd,
+    fermat_factoring::FermatFactoring,
+    gaussian_int::{cornacchia, GaussianInt},
+    gcd::{gcd_all, lcm_all, Gcd},
+    integer_roots::IntegerRoots,
+    linear_congruence::LinearCongruence,
+    linear_diophantine::LinearDiophantine,
+    modular_exponentiation::ModularExponentiation,
+    modular_int::{DynModInt, ModInt, Zn},
     modulus::Modulus,
-    multiples::{multiples, sum_of_multiples},
     parity::Parity,
     primality::{Primality, WilsonsPrimality},
+    pseudoprime::Pseudoprime,
+    quadratic_residue::QuadraticResidue,
+};
+
+#[cfg(feature = "alloc")]
+pub use crate::number_theory::{
+    batch_gcd::{batch_gcd, shared_factors},
+    continued_fraction::ContinuedFraction,
+    multiples::{
+        count_of_multiples, multiples, multiples_in, sum_of_multiples, sum_of_powers_of_multiples,
+        Multiples, MultiplesOf,
+    },
+    primality::AksPrimality,
+    prime_counting::{
+        legendre, lucy_hedgehog, meissel_lehmer, prime_count, segmented_sieve, PrimeCounting,
+    },
+    pseudoprime::{
+        carmichael_numbers, euler_pseudoprimes, fermat_pseudoprimes, strong_pseudoprimes,
+    },
+    pythagorean_triples::{
+        berggren_tree, classify_triple, primitive_triples, pythagorean_triples, triples_with_leg,
+        BerggrenTree, PrimitiveTriples, PythagoreanTriple, TripleBound, TripleClass,
+    },
+    smooth_factoring::SmoothFactoring,
+    sum_of_squares::SumOfSquares,
+};
+
+#[cfg(feature = "std")]
+pub use crate::number_theory::{
+    certificate::{
+        PocklingtonCertificate, PocklingtonFactor, PrattCertificate, PrimalityCertificates,
+    },
+    ecm::{ecm, EcmConfig},
+    factorization::{pollard_rho, BigPrimality, FactorizationStrategy},
+    factorization_cache::FactorizationCache,
+    quadratic_sieve::{quadratic_sieve, quadratic_sieve_u128},
+    random::{
+        random_coprime_pair, random_prime, random_safe_prime, random_sophie_germain_prime,
+        random_strong_prime, random_unit,
+    },
+};
+
+#[cfg(feature = "rayon")]
+pub use crate::number_theory::parallel::{
+    par_euler_totients, par_prime_count, par_prime_factorizations, par_segmented_sieve,
 };